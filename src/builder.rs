@@ -5,6 +5,8 @@
 //! specified parameters. Specifically, when any of [`follow`][Builder::follow],
 //! [`track`][Builder::track] and [`locations`][Builder::locations] parameters is specified,
 //! `filter` will be used, and when none is specified, `sample` will be used.
+//! You can also choose the endpoint explicitly with [`for_filter`][Builder::for_filter] and
+//! [`for_sample`][Builder::for_sample].
 //!
 //! [`POST statuses/filter`]: https://developer.twitter.com/en/docs/tweets/filter-realtime/api-reference/post-statuses-filter
 //! [`GET statuses/sample`]: https://developer.twitter.com/en/docs/tweets/filter-realtime/api-reference/post-statuses-filter
@@ -12,6 +14,12 @@
 //! `filter` yields public Tweets that match the filter predicates specified by the parameters,
 //! and `sample` yields "a small random sample" of all public Tweets.
 //!
+//! `Builder` can also connect to the Twitter API v2 [filtered stream] with
+//! [`Builder::v2_filtered_stream`]. The v2 filtered stream is authorized with a bearer token and
+//! delivers Tweets matching the rules registered beforehand with the rules endpoint.
+//!
+//! [filtered stream]: https://developer.twitter.com/en/docs/twitter-api/tweets/filtered-stream/introduction
+//!
 //! ## Example
 //!
 //! ```rust,no_run
@@ -40,36 +48,125 @@
 //! ```
 
 mod bounding_box;
+mod extra;
+mod language;
+#[cfg(feature = "json")]
+mod lookup;
 
+pub use http::uri::InvalidUri;
 pub use http::Method as RequestMethod;
 pub use http::Uri;
 
-pub use bounding_box::BoundingBox;
+pub use bounding_box::{BoundingBox, InvalidBoundingBox};
+pub use language::Language;
 
 use std::borrow::Cow;
 use std::fmt::{self, Formatter};
+use std::num::NonZeroU64;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+#[cfg(feature = "json")]
+use std::sync::Mutex;
+#[cfg(feature = "tokio")]
+use std::time::Duration;
 
-use http::header::{HeaderValue, AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE};
+#[cfg(feature = "tokio")]
+use bytes::Bytes;
+#[cfg(feature = "tokio")]
+use futures_util::future::{AbortHandle, Abortable};
+#[cfg(feature = "gzip")]
+use http::header::ACCEPT_ENCODING;
+use http::header::{HeaderValue, AUTHORIZATION, CONTENT_LENGTH, CONTENT_TYPE, USER_AGENT};
 use http::Request;
+#[cfg(feature = "tokio")]
+use http_body::Body;
 use slice_of_array::SliceFlatExt;
 
+use crate::error::ParameterError;
 use crate::service::HttpService;
 use crate::util::fmt_join;
-use crate::{FutureTwitterStream, Token};
+use crate::{BearerToken, Config, FutureTwitterStream, Token};
+
+use private::{OAuthOptions, Sealed};
 
 /// A builder for [`TwitterStream`](crate::TwitterStream).
 ///
 /// See the [`builder`][crate::builder] module documentation for details.
+///
+/// Two `Builder`s compare equal if they would make the same request with the same options.
+/// The callbacks set with `on_warning` and the counters set with `rate_limit_counter` are
+/// compared by identity, i.e. a clone of a `Builder` compares equal to the original but two
+/// `Builder`s given the same closure separately do not.
+/// The coordinates of the [`locations`](Builder::locations) are compared bitwise rather than
+/// with `f64`'s `==`, so that `Builder` can implement `Eq`. This means that a `NaN` coordinate
+/// equals itself, and that `-0.0` does not equal `0.0`, which are formatted differently in
+/// the request.
 #[derive(Clone, Debug)]
 pub struct Builder<'a, T = Token> {
     token: T,
     endpoint: Option<(RequestMethod, Uri)>,
     parameters: Parameters<'a>,
+    user_agent: HeaderValue,
+    oauth: OAuthOptions<'a>,
+    config: Config,
+}
+
+/// A callback set with `Builder::on_warning`, which is shared among the clones of `Builder`.
+#[cfg(feature = "json")]
+#[derive(Clone)]
+pub(crate) struct WarningCallback(Arc<Mutex<dyn FnMut(crate::error::Warning) + Send>>);
+
+/// The request that [`Builder::dry_run`] would send, as plain strings.
+///
+/// The `Display` implementation formats the request as a `curl` command.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct DryRun {
+    /// The HTTP method, e.g. `"POST"`.
+    pub method: String,
+    /// The URI of the endpoint, including the query string for a `GET` request.
+    pub uri: String,
+    /// The value of the `Authorization` header.
+    pub authorization: String,
+    /// The form-encoded body, which is empty for a `GET` request.
+    pub body: String,
 }
 
+/// A counter of consecutive rate-limited connection attempts, which doubles the `backoff` of
+/// [`Error::RateLimited`](crate::Error::RateLimited).
+///
+/// Without a counter, every rate-limited attempt reports the initial back-off of 1 minute.
+/// With a counter set with [`Builder::rate_limit_counter`], the back-off doubles on each
+/// consecutive rate-limited attempt, up to 32 minutes, as Twitter recommends. The count is reset
+/// when a connection succeeds.
+///
+/// The clones of a counter share the same count. Twitter limits the connection attempts per
+/// account, so you may want to set the same counter on the `Builder`s connecting with the same
+/// credentials. Two counters compare equal if they share the count.
+///
+/// # Example
+///
+/// ```
+/// use twitter_stream::builder::RateLimitCounter;
+///
+/// # let token = twitter_stream::Token::from_parts("", "", "", "");
+/// let counter = RateLimitCounter::new();
+/// let mut builder = twitter_stream::Builder::new(token);
+/// builder.track("@Twitter").rate_limit_counter(counter.clone());
+/// assert_eq!(counter.attempts(), 0);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RateLimitCounter(Arc<AtomicU32>);
+
 /// Parameters to the Streaming API.
 #[derive(Clone, Debug, Default, oauth::Request)]
 struct Parameters<'a> {
+    #[oauth1(encoded)]
+    backfill_minutes: Option<u32>,
+    #[oauth1(skip_if = str::is_empty)]
+    expansions: Cow<'a, str>,
+    #[oauth1(fmt = fmt_delimited, skip_if = not)]
+    delimited: bool,
     #[oauth1(skip_if = not)]
     stall_warnings: bool,
     filter_level: Option<FilterLevel>,
@@ -84,8 +181,25 @@ struct Parameters<'a> {
     locations: Cow<'a, [BoundingBox]>,
     #[oauth1(encoded)]
     count: Option<i32>,
+    #[oauth1(encoded, fmt = fmt_replies, skip_if = not)]
+    replies: bool,
+    #[oauth1(rename = "tweet.fields", skip_if = str::is_empty)]
+    tweet_fields: Cow<'a, str>,
+    with: Option<StreamScope<'a>>,
+    // Serialized by `extra::WithExtra`.
+    #[oauth1(skip)]
+    extra: extra::Extra<'a>,
 }
 
+/// Credentials that can authorize requests to the Streaming API.
+///
+/// This trait is implemented by [`Token`] (OAuth 1.0a user context), [`BearerToken`]
+/// (OAuth 2.0 application-only) and [`Auth`](crate::Auth), which can be either of them.
+/// It is sealed and cannot be implemented outside of this crate.
+pub trait Authorize: Sealed {}
+
+impl<T: Sealed> Authorize for T {}
+
 str_enum! {
     /// Represents the [`filter_level`] parameter in API requests.
     ///
@@ -101,34 +215,204 @@ str_enum! {
     }
 }
 
+/// Represents the `with` parameter of the deprecated user streams, which scopes the accounts
+/// whose activities are delivered.
+#[derive(Clone, Debug, PartialEq, Hash, Eq)]
+pub enum StreamScope<'a> {
+    /// `"user"`, the activities of the authenticating user only.
+    User,
+    /// `"followings"`, the activities of the authenticating user and the users they follow.
+    Followings,
+    /// A value that is not listed above.
+    Custom(Cow<'a, str>),
+}
+
 const FILTER: &str = "https://stream.twitter.com/1.1/statuses/filter.json";
 const SAMPLE: &str = "https://stream.twitter.com/1.1/statuses/sample.json";
+const FILTERED_STREAM_V2: &str = "https://api.twitter.com/2/tweets/search/stream";
+const USER_AGENT_DEFAULT: &str = concat!("twitter-stream-rs/", env!("CARGO_PKG_VERSION"));
 
-impl<'a, C, A> Builder<'a, Token<C, A>>
-where
-    C: AsRef<str>,
-    A: AsRef<str>,
-{
+impl<'a, T: Authorize> Builder<'a, T> {
     /// Creates a builder.
-    pub fn new(token: Token<C, A>) -> Self {
+    pub fn new(token: T) -> Self {
         Builder {
             token,
             endpoint: None,
             parameters: Parameters::default(),
+            user_agent: HeaderValue::from_static(USER_AGENT_DEFAULT),
+            oauth: OAuthOptions::default(),
+            config: Config::default(),
+        }
+    }
+
+    /// Creates a builder connecting to `uri` with `method`, taking the parameters from the query
+    /// string of `uri`.
+    ///
+    /// The parameters known to `Builder` (like `track`, `follow` and `language`) are set as if
+    /// with the corresponding methods, and the others are kept as with [`param`](Builder::param),
+    /// so that they are included in the OAuth signature. A known parameter whose value cannot be
+    /// parsed (e.g. a non-numeric `follow`) is kept as-is as well. The parameters are sent in
+    /// the body of a `POST` request as usual.
+    ///
+    /// This is useful for migrating a configuration that stores the whole URI of the stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParameterError::InvalidName`] if the name of an unknown parameter is not
+    /// valid for [`param`](Builder::param).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use twitter_stream::builder::{RequestMethod, Uri};
+    /// use twitter_stream::{BearerToken, Builder};
+    ///
+    /// let uri = Uri::from_static(
+    ///     "https://stream.twitter.com/1.1/statuses/filter.json?track=%40Twitter&tweet_mode=extended",
+    /// );
+    /// let req = Builder::from_uri(RequestMethod::POST, uri, BearerToken("AAAA"))
+    ///     .unwrap()
+    ///     .build_request();
+    ///
+    /// assert_eq!(req.uri(), "https://stream.twitter.com/1.1/statuses/filter.json");
+    /// assert_eq!(req.body(), b"track=%40Twitter&tweet_mode=extended");
+    /// ```
+    pub fn from_uri(method: RequestMethod, uri: Uri, token: T) -> Result<Self, ParameterError> {
+        let mut builder = Builder::new(token);
+
+        let mut parts = uri.into_parts();
+        if let Some(ref path_and_query) = parts.path_and_query {
+            if let Some(query) = path_and_query.query() {
+                for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+                    let (key, value) = match pair.find('=') {
+                        Some(i) => (&pair[..i], &pair[i + 1..]),
+                        None => (pair, ""),
+                    };
+                    builder.query_param(decode_component(key), decode_component(value))?;
+                }
+                let path = path_and_query.path().parse().unwrap();
+                parts.path_and_query = Some(path);
+            }
         }
+        let uri = Uri::from_parts(parts).unwrap();
+
+        builder.endpoint((method, uri));
+        Ok(builder)
     }
 
     /// Start listening on the Streaming API endpoint, returning a `Future` which resolves
     /// to a `Stream` yielding JSON messages from the API.
     ///
+    /// The HTTPS connection is made with `native-tls` if the `tls-native` feature is enabled
+    /// (the default, through the `hyper` feature), or with `rustls` and the Mozilla root
+    /// certificates if the `tls-rustls` feature is enabled instead. Only one of them should be
+    /// enabled, and `tls-native` takes precedence if both are. To customize the TLS
+    /// configuration, use [`listen_with_connector`](Builder::listen_with_connector).
+    ///
+    /// # Errors
+    ///
+    /// The `Future` resolves to an [`Error::InvalidParameter`](crate::Error::InvalidParameter)
+    /// without sending the request if the `follow`, `track` or `locations` parameter
+    /// has more items than the Streaming API allows.
+    ///
     /// # Panics
     ///
     /// This will panic if the underlying HTTPS connector failed to initialize.
-    #[cfg(feature = "hyper")]
-    #[cfg_attr(docsrs, doc(cfg(feature = "hyper")))]
+    /// Use [`try_listen`](Builder::try_listen) to handle the failure instead.
+    #[cfg(any(feature = "tls-native", feature = "tls-rustls"))]
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "tls-native", feature = "tls-rustls"))))]
     pub fn listen(&self) -> crate::hyper::FutureTwitterStream {
-        let conn = hyper_tls::HttpsConnector::new();
-        self.listen_with_client(hyper_pkg::Client::builder().build::<_, hyper_pkg::Body>(conn))
+        self.try_listen()
+            .expect("failed to initialize the TLS connector")
+    }
+
+    /// Same as [`listen`](Builder::listen) except that it returns an
+    /// [`Error::Tls`](crate::Error::Tls) instead of panicking if the underlying HTTPS connector
+    /// failed to initialize.
+    ///
+    /// The `rustls` connector of the `tls-rustls` feature never fails to initialize.
+    #[cfg_attr(not(feature = "tls-native"), allow(broken_intra_doc_links))]
+    #[cfg(any(feature = "tls-native", feature = "tls-rustls"))]
+    #[cfg_attr(docsrs, doc(cfg(any(feature = "tls-native", feature = "tls-rustls"))))]
+    pub fn try_listen(&self) -> Result<crate::hyper::FutureTwitterStream, crate::hyper::Error> {
+        #[cfg(feature = "tls-native")]
+        let conn = {
+            #[allow(unused_mut)]
+            let mut tls = native_tls::TlsConnector::builder();
+            #[cfg(feature = "http2")]
+            if self.config.http2_only {
+                tls.request_alpns(&["h2"]);
+            }
+            let tls = tls.build().map_err(crate::Error::Tls)?;
+            let mut http = hyper_pkg::client::HttpConnector::new();
+            http.enforce_http(false);
+            hyper_tls::HttpsConnector::from((http, tls.into()))
+        };
+        #[cfg(not(feature = "tls-native"))]
+        let conn = {
+            let builder = hyper_rustls::HttpsConnectorBuilder::new()
+                .with_webpki_roots()
+                .https_or_http();
+            #[cfg(feature = "http2")]
+            if self.config.http2_only {
+                return Ok(self.listen_with_connector(builder.enable_http2().build()));
+            }
+            builder.enable_http1().build()
+        };
+        Ok(self.listen_with_connector(conn))
+    }
+
+    /// Same as [`listen`](Builder::listen) except that it uses `connector` to connect to the
+    /// endpoint.
+    ///
+    /// This is useful for customizing the TLS configuration (e.g. the root certificates) without
+    /// building a whole HTTP client. `connector` must be able to handle the `https` scheme.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # let token = twitter_stream::Token::from_parts("", "", "", "");
+    /// let tls = native_tls::TlsConnector::builder()
+    ///     .min_protocol_version(Some(native_tls::Protocol::Tlsv12))
+    ///     .build()
+    ///     .unwrap();
+    /// let mut http = hyper_pkg::client::HttpConnector::new();
+    /// http.enforce_http(false);
+    /// let conn = hyper_tls::HttpsConnector::from((http, tls.into()));
+    ///
+    /// let stream = twitter_stream::Builder::new(token)
+    ///     .track("@Twitter")
+    ///     .listen_with_connector(conn);
+    /// ```
+    #[cfg(feature = "hyper-client")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "hyper-client")))]
+    pub fn listen_with_connector<C>(&self, connector: C) -> crate::hyper::FutureTwitterStream
+    where
+        C: hyper_pkg::client::connect::Connect + Clone + Send + Sync + 'static,
+    {
+        #[allow(unused_mut)]
+        let mut client = hyper_pkg::Client::builder();
+        #[cfg(feature = "http2")]
+        client.http2_only(self.config.http2_only);
+        let client = client.build::<_, hyper_pkg::Body>(connector);
+        self.listen_with_client(client)
+    }
+
+    /// Set whether [`listen`](Builder::listen) connects to the endpoint with HTTP/2 only.
+    ///
+    /// When this is set, `listen` and [`try_listen`](Builder::try_listen) request the `h2`
+    /// protocol in the TLS handshake (ALPN) and speak HTTP/2 without falling back to HTTP/1.1.
+    /// This is intended for the Twitter API v2 endpoints like
+    /// [`v2_filtered_stream`](Builder::v2_filtered_stream), which are served over HTTP/2.
+    ///
+    /// With [`listen_with_connector`](Builder::listen_with_connector), `connector` is
+    /// responsible for negotiating HTTP/2, e.g. by requesting `h2` with ALPN. This has no effect
+    /// on [`listen_with_client`](Builder::listen_with_client).
+    #[cfg(feature = "http2")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "http2")))]
+    pub fn http2_only(&mut self, http2_only: bool) -> &mut Self {
+        self.config.http2_only = http2_only;
+        self
     }
 
     /// Same as [`listen`](Builder::listen) except that it uses `client` to make HTTP request
@@ -136,6 +420,9 @@ where
     ///
     /// `client` must be able to handle the `https` scheme.
     ///
+    /// The parameters are validated in the same way as `listen`, in which case `client` is not
+    /// called.
+    ///
     /// # Panics
     ///
     /// This will call `<S as Service>::call` without checking for `<S as Service>::poll_ready`
@@ -161,18 +448,203 @@ where
         S: HttpService<B>,
         B: From<Vec<u8>>,
     {
-        let req = prepare_request(
+        if let Err(e) = self.parameters.validate() {
+            return FutureTwitterStream {
+                response: None,
+                invalid_parameter: Some(e),
+                erroring: None,
+                connect_timeout: None,
+                stream: None,
+                config: self.config.clone(),
+            };
+        }
+
+        let response = client.call(self.build_request().map(Into::into));
+
+        FutureTwitterStream {
+            response: Some(response),
+            invalid_parameter: None,
+            erroring: None,
+            connect_timeout: None,
+            stream: None,
+            config: self.config.clone(),
+        }
+    }
+
+    /// Connects to the endpoint with `client` and spawns a Tokio task calling `f` with every
+    /// message from the stream.
+    ///
+    /// This is a shorthand for spawning
+    /// `listen_with_client(client).try_flatten_stream().try_for_each(...)`.
+    /// The task ends with an error if the connection fails or the stream yields an error,
+    /// and with `Ok(())` if the stream ends or the task is stopped with the returned
+    /// [`AbortHandle`], which closes the connection. To reconnect automatically, wrap `client`
+    /// with [`Reconnect`](crate::service::Reconnect).
+    ///
+    /// Errors from the response body are converted into the error type of `client` with
+    /// [`From`].
+    ///
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a Tokio runtime.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let token = twitter_stream::Token::from_parts("", "", "", "");
+    /// let conn = hyper_tls::HttpsConnector::new();
+    /// let client = hyper_pkg::Client::builder().build::<_, hyper_pkg::Body>(conn);
+    ///
+    /// let (task, abort) = twitter_stream::Builder::new(token)
+    ///     .track("@Twitter")
+    ///     .spawn(client, |json| println!("{}", json));
+    ///
+    /// // Stop after a minute.
+    /// tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+    /// abort.abort();
+    /// task.await.unwrap().unwrap();
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    #[allow(clippy::type_complexity)]
+    pub fn spawn<S, B, F>(
+        &self,
+        client: S,
+        mut f: F,
+    ) -> (
+        tokio::task::JoinHandle<Result<(), crate::Error<S::Error>>>,
+        AbortHandle,
+    )
+    where
+        S: HttpService<B>,
+        S::Future: Send + 'static,
+        S::ResponseBody: Send + 'static,
+        S::Error: From<<S::ResponseBody as Body>::Error> + Send + 'static,
+        B: From<Vec<u8>>,
+        F: FnMut(string::String<Bytes>) + Send + 'static,
+    {
+        let future = self.listen_with_client(client);
+        let task = async move {
+            let mut stream = Box::pin(future.await?);
+            while let Some(message) = crate::util::Next(&mut stream).await {
+                f(message.map_err(crate::Error::convert)?);
+            }
+            Ok(())
+        };
+        let (abort, registration) = AbortHandle::new_pair();
+        let task = Abortable::new(task, registration);
+        let handle = tokio::spawn(async move { task.await.unwrap_or(Ok(())) });
+        (handle, abort)
+    }
+
+    /// Creates an HTTP request to the Streaming API endpoint without sending it.
+    ///
+    /// This is useful if you want to send the request with an HTTP client that does not implement
+    /// [`HttpService`], or to inspect the request (e.g. the `Authorization` header) in tests.
+    /// The response body can then be passed to [`TwitterStream::new`](crate::TwitterStream::new).
+    ///
+    /// Unlike [`listen`](Builder::listen), this does not validate the parameters.
+    #[cfg_attr(
+        not(any(feature = "tls-native", feature = "tls-rustls")),
+        allow(broken_intra_doc_links)
+    )]
+    pub fn build_request(&self) -> Request<Vec<u8>> {
+        prepare_request(
             self.endpoint.as_ref(),
-            self.token.as_ref(),
+            &self.token,
             &self.parameters,
-        );
-        let response = client.call(req.map(Into::into));
+            &self.user_agent,
+            &self.oauth,
+        )
+    }
+
+    /// Returns the method, URI, `Authorization` header and body of the request to the Streaming
+    /// API as plain strings, without sending it.
+    ///
+    /// This is useful for debugging authentication problems: sending the request with another
+    /// HTTP client like `curl` tells whether the problem is in the credentials or in the client.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use twitter_stream::{BearerToken, Builder};
+    ///
+    /// let dry_run = Builder::new(BearerToken("AAAA")).track("@Twitter").dry_run();
+    /// assert_eq!(dry_run.method, "POST");
+    /// assert_eq!(dry_run.uri, "https://stream.twitter.com/1.1/statuses/filter.json");
+    /// assert_eq!(dry_run.authorization, "Bearer AAAA");
+    /// assert_eq!(dry_run.body, "track=%40Twitter");
+    /// println!("{}", dry_run);
+    /// ```
+    pub fn dry_run(&self) -> DryRun {
+        let req = self.build_request();
+        DryRun {
+            method: req.method().to_string(),
+            uri: req.uri().to_string(),
+            authorization: req.headers()[AUTHORIZATION]
+                .to_str()
+                .unwrap_or_default()
+                .to_owned(),
+            body: String::from_utf8(req.into_body()).unwrap_or_default(),
+        }
+    }
+
+    /// Same as [`build_request`](Builder::build_request) except that it consumes the builder.
+    pub fn into_request(self) -> Request<Vec<u8>> {
+        self.build_request()
+    }
+}
 
-        FutureTwitterStream { response }
+impl<'a, T: AsRef<str>> Builder<'a, BearerToken<T>> {
+    /// Creates a builder for the Twitter API v2 [filtered stream][1] endpoint
+    /// (`GET /2/tweets/search/stream`).
+    ///
+    /// The request is authorized with the given bearer token instead of OAuth 1.0a.
+    ///
+    /// The v2 filtered stream does not take the `follow`, `track` or `locations` parameters.
+    /// Instead, it delivers Tweets matching the rules added to the
+    /// [`POST /2/tweets/search/stream/rules`][2] endpoint.
+    ///
+    /// [1]: https://developer.twitter.com/en/docs/twitter-api/tweets/filtered-stream/api-reference/get-tweets-search-stream
+    /// [2]: https://developer.twitter.com/en/docs/twitter-api/tweets/filtered-stream/api-reference/post-tweets-search-stream-rules
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// twitter_stream::Builder::v2_filtered_stream("bearer_token")
+    ///     .tweet_fields("created_at,lang")
+    ///     .expansions("author_id")
+    ///     .listen()
+    ///     .try_flatten_stream()
+    ///     .try_for_each(|json| {
+    ///         println!("{}", json);
+    ///         future::ok(())
+    ///     })
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub fn v2_filtered_stream(bearer_token: T) -> Self {
+        Builder {
+            token: BearerToken(bearer_token),
+            endpoint: Some((RequestMethod::GET, Uri::from_static(FILTERED_STREAM_V2))),
+            parameters: Parameters::default(),
+            user_agent: HeaderValue::from_static(USER_AGENT_DEFAULT),
+            oauth: OAuthOptions::default(),
+            config: Config::default(),
+        }
     }
 }
 
-impl<'a, C, A> Builder<'a, Token<C, A>> {
+impl<'a, T> Builder<'a, T> {
     /// Set the API endpoint URI to be connected.
     ///
     /// This overrides the default behavior of automatically determining the endpoint to use.
@@ -181,17 +653,325 @@ impl<'a, C, A> Builder<'a, Token<C, A>> {
         self
     }
 
+    /// Same as [`endpoint`](Builder::endpoint) except that it parses `uri` from a string,
+    /// e.g. the URI of a custom endpoint kept in a configuration file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error without changing the endpoint if `uri` is not a valid URI.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use twitter_stream::builder::RequestMethod;
+    ///
+    /// # let token = twitter_stream::Token::from_parts("", "", "", "");
+    /// let mut builder = twitter_stream::Builder::new(token);
+    /// builder
+    ///     .endpoint_str(RequestMethod::GET, "https://stream.example.com/1.1/statuses/sample.json")
+    ///     .unwrap();
+    /// assert_eq!(
+    ///     builder.build_request().uri(),
+    ///     "https://stream.example.com/1.1/statuses/sample.json",
+    /// );
+    ///
+    /// assert!(builder.endpoint_str(RequestMethod::GET, "not a URI").is_err());
+    /// ```
+    pub fn endpoint_str(
+        &mut self,
+        method: RequestMethod,
+        uri: &str,
+    ) -> Result<&mut Self, InvalidUri> {
+        let uri = uri.parse::<Uri>()?;
+        self.endpoint = Some((method, uri));
+        Ok(self)
+    }
+
+    /// Use the [PowerTrack][1] endpoint of the enterprise API for `account` and `stream_label`,
+    /// i.e. `https://gnip-stream.twitter.com/stream/powertrack/accounts/{account}/publishers/twitter/{stream_label}.json`.
+    ///
+    /// PowerTrack authenticates the requests with HTTP Basic authentication, so use
+    /// [`Auth::Basic`](crate::Auth::Basic) as the token. The rules are managed with a separate
+    /// API, and the standard parameters like `track` should not be set.
+    ///
+    /// [1]: https://developer.twitter.com/en/docs/twitter-api/enterprise/powertrack-api/overview
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParameterError::InvalidPowerTrack`] without changing the endpoint if `account`
+    /// or `stream_label` is empty or contains a character other than ASCII alphanumerics, `-`,
+    /// `.`, `_` and `~`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use twitter_stream::Auth;
+    ///
+    /// let auth = Auth::Basic {
+    ///     username: "user@example.com",
+    ///     password: "password",
+    /// };
+    /// let req = twitter_stream::Builder::new(auth)
+    ///     .powertrack("my-account", "prod")
+    ///     .unwrap()
+    ///     .build_request();
+    /// assert_eq!(
+    ///     req.uri(),
+    ///     "https://gnip-stream.twitter.com/stream/powertrack/accounts/my-account/publishers/twitter/prod.json",
+    /// );
+    /// assert_eq!(
+    ///     req.headers()["authorization"],
+    ///     "Basic dXNlckBleGFtcGxlLmNvbTpwYXNzd29yZA==",
+    /// );
+    /// ```
+    pub fn powertrack(
+        &mut self,
+        account: &str,
+        stream_label: &str,
+    ) -> Result<&mut Self, ParameterError> {
+        for &s in &[account, stream_label] {
+            if !is_unreserved(s) {
+                return Err(ParameterError::InvalidPowerTrack(s.to_owned()));
+            }
+        }
+        let uri = format!(
+            "https://gnip-stream.twitter.com/stream/powertrack/accounts/{}/publishers/twitter/{}.json",
+            account, stream_label,
+        );
+        let uri = uri.parse::<Uri>().unwrap();
+        Ok(self.endpoint((RequestMethod::GET, uri)))
+    }
+
+    /// Use the [`GET statuses/sample`][1] endpoint regardless of the other parameters.
+    ///
+    /// This is a shorthand for
+    /// `endpoint((RequestMethod::GET, Uri::from_static("https://stream.twitter.com/1.1/statuses/sample.json")))`.
+    ///
+    /// [1]: https://developer.twitter.com/en/docs/tweets/sample-realtime/api-reference/get-statuses-sample
+    pub fn for_sample(&mut self) -> &mut Self {
+        self.endpoint((RequestMethod::GET, Uri::from_static(SAMPLE)))
+    }
+
+    /// Use the [`POST statuses/filter`][1] endpoint regardless of the other parameters.
+    ///
+    /// This is a shorthand for
+    /// `endpoint((RequestMethod::POST, Uri::from_static("https://stream.twitter.com/1.1/statuses/filter.json")))`.
+    ///
+    /// [1]: https://developer.twitter.com/en/docs/tweets/filter-realtime/api-reference/post-statuses-filter
+    pub fn for_filter(&mut self) -> &mut Self {
+        self.endpoint((RequestMethod::POST, Uri::from_static(FILTER)))
+    }
+
+    /// Set an extra parameter that is not covered by the other methods of `Builder`,
+    /// like `tweet_mode`.
+    ///
+    /// The parameter is sent along with the other parameters and included in the OAuth
+    /// signature. Setting the same `key` again replaces the previous value. The `key` should
+    /// not be one of the parameters set by the other methods.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParameterError::InvalidName`] if `key` is empty or contains a character
+    /// other than ASCII alphanumerics, `-`, `.`, `_` and `~`, which would need percent-encoding.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let token = twitter_stream::Token::from_parts("", "", "", "");
+    /// let req = twitter_stream::Builder::new(token)
+    ///     .track("@Twitter")
+    ///     .param("tweet_mode", "extended")
+    ///     .unwrap()
+    ///     .build_request();
+    /// assert_eq!(req.body(), b"track=%40Twitter&tweet_mode=extended");
+    /// ```
+    pub fn param(
+        &mut self,
+        key: impl Into<Cow<'a, str>>,
+        value: impl Into<Cow<'a, str>>,
+    ) -> Result<&mut Self, ParameterError> {
+        let key = key.into();
+        if !is_unreserved(&key) {
+            return Err(ParameterError::InvalidName(key.into_owned()));
+        }
+        self.parameters.extra.insert(key, value.into());
+        Ok(self)
+    }
+
+    /// Sets a parameter taken from a query string, falling back to `param` if `key` is unknown
+    /// or `value` cannot be parsed.
+    fn query_param(&mut self, key: String, value: String) -> Result<(), ParameterError> {
+        fn parse_list<T: std::str::FromStr>(value: &str) -> Option<Vec<T>> {
+            value.split(',').map(|v| v.parse().ok()).collect()
+        }
+
+        fn parse_locations(value: &str) -> Option<Vec<BoundingBox>> {
+            let coordinates = parse_list::<f64>(value)?;
+            if coordinates.len() % 4 != 0 {
+                return None;
+            }
+            coordinates
+                .chunks(4)
+                .map(|c| BoundingBox::try_new(c[0], c[1], c[2], c[3]).ok())
+                .collect()
+        }
+
+        let p = &mut self.parameters;
+        match &*key {
+            "track" => p.track = value.into(),
+            "language" => p.language = value.into(),
+            "expansions" => p.expansions = value.into(),
+            "tweet.fields" => p.tweet_fields = value.into(),
+            "delimited" if value == "length" => {
+                self.delimited(true);
+            }
+            "stall_warnings" if value == "true" => p.stall_warnings = true,
+            "replies" if value == "all" => p.replies = true,
+            "with" => {
+                p.with = Some(match &*value {
+                    "user" => StreamScope::User,
+                    "followings" => StreamScope::Followings,
+                    _ => StreamScope::Custom(value.into()),
+                })
+            }
+            _ => {
+                let parsed = match &*key {
+                    "follow" => parse_list(&value).map(|v| p.follow = v.into()),
+                    "locations" => parse_locations(&value).map(|v| p.locations = v.into()),
+                    "filter_level" => value.parse().ok().map(|v| p.filter_level = Some(v)),
+                    "count" => value.parse().ok().map(|v| p.count = Some(v)),
+                    "backfill_minutes" => value.parse().ok().map(|v| p.backfill_minutes = Some(v)),
+                    _ => None,
+                };
+                if parsed.is_none() {
+                    self.param(key, value)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Set the `User-Agent` header of the request.
+    ///
+    /// Defaults to `twitter-stream-rs/<version>`, where `<version>` is the version of this crate.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use http::header::{HeaderValue, USER_AGENT};
+    ///
+    /// # let token = twitter_stream::Token::from_parts("", "", "", "");
+    /// let req = twitter_stream::Builder::new(token)
+    ///     .user_agent(HeaderValue::from_static("my-bot/1.0"))
+    ///     .build_request();
+    /// assert_eq!(req.headers()[USER_AGENT], "my-bot/1.0");
+    /// ```
+    pub fn user_agent(&mut self, user_agent: impl Into<HeaderValue>) -> &mut Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Set the `oauth_nonce` value of the OAuth 1.0a signature, or unset it with `None`.
+    ///
+    /// By default, a random nonce is generated for each request. Setting the nonce together
+    /// with [`oauth_timestamp`](Builder::oauth_timestamp) makes the `Authorization` header
+    /// reproducible, which is useful for snapshot-testing the requests. Do not set it when
+    /// connecting to the real API, which rejects a nonce used more than once.
+    ///
+    /// This has no effect on requests authorized with a bearer token.
+    pub fn oauth_nonce(&mut self, nonce: impl Into<Option<&'a str>>) -> &mut Self {
+        self.oauth.nonce = nonce.into();
+        self
+    }
+
+    /// Set the `oauth_timestamp` value of the OAuth 1.0a signature, or unset it with `None`.
+    ///
+    /// By default, the time when the request is created is used. A timestamp of `0` is treated
+    /// as unset. See [`oauth_nonce`](Builder::oauth_nonce) for the use case.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use http::header::AUTHORIZATION;
+    ///
+    /// let token = twitter_stream::Token::from_parts("ck", "cs", "ak", "as");
+    /// let mut builder = twitter_stream::Builder::new(token);
+    /// builder
+    ///     .track("@Twitter")
+    ///     .oauth_nonce("Dk-OGluFEQ4f")
+    ///     .oauth_timestamp(1234567890);
+    /// assert_eq!(
+    ///     builder.build_request().headers()[AUTHORIZATION],
+    ///     builder.build_request().headers()[AUTHORIZATION],
+    /// );
+    /// ```
+    pub fn oauth_timestamp(&mut self, timestamp: impl Into<Option<u64>>) -> &mut Self {
+        self.oauth.timestamp = timestamp.into().and_then(NonZeroU64::new);
+        self
+    }
+
     /// Reset the token to be used to log into Twitter.
-    pub fn token(&mut self, token: Token<C, A>) -> &mut Self {
+    pub fn token(&mut self, token: T) -> &mut Self {
         self.token = token;
         self
     }
 
+    /// Set whether to request the messages to be prefixed by their lengths in bytes
+    /// (`delimited=length`).
+    ///
+    /// When this is set, `TwitterStream` reads each message according to its length prefix
+    /// instead of looking for the CRLF at its end. The yielded messages are the same either way.
+    ///
+    /// See the [Twitter Developer Documentation][1] for more information.
+    ///
+    /// [1]: https://developer.twitter.com/en/docs/tweets/filter-realtime/guides/basic-stream-parameters#delimited
+    pub fn delimited(&mut self, delimited: bool) -> &mut Self {
+        self.parameters.delimited = delimited;
+        self.config.delimited = delimited;
+        self
+    }
+
     /// Set whether to receive messages when in danger of being disconnected.
     ///
+    /// The stall warnings are yielded by the stream like any other message. They can be
+    /// deserialized as [`Warning`](crate::error::Warning) (requires the `json` feature) or
+    /// handled with [`on_warning`](Builder::on_warning), which also sets this parameter.
+    ///
     /// See the [Twitter Developer Documentation][1] for more information.
     ///
     /// [1]: https://developer.twitter.com/en/docs/tweets/filter-realtime/guides/basic-stream-parameters#stall-warnings
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # #[cfg(feature = "json")]
+    /// # {
+    /// use futures::prelude::*;
+    /// use twitter_stream::error::Warning;
+    /// use twitter_stream::TwitterStream;
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// #[serde(untagged)]
+    /// enum Message {
+    ///     Warning { warning: Warning },
+    ///     Tweet { id: u64 },
+    /// }
+    ///
+    /// # futures::executor::block_on(async {
+    /// let body = hyper_pkg::Body::from(concat!(
+    ///     r#"{"warning":{"code":"FALLING_BEHIND","message":"","percent_full":60}}"#,
+    ///     "\r\n",
+    /// ));
+    /// let mut messages = TwitterStream::new(body).parse::<Message>();
+    ///
+    /// match messages.try_next().await.unwrap().unwrap() {
+    ///     Message::Warning { warning } => assert_eq!(warning.percent_full, Some(60)),
+    ///     Message::Tweet { .. } => unreachable!(),
+    /// }
+    /// # });
+    /// # }
+    /// ```
+    #[cfg_attr(not(feature = "json"), allow(broken_intra_doc_links))]
     pub fn stall_warnings(&mut self, stall_warnings: bool) -> &mut Self {
         self.parameters.stall_warnings = stall_warnings;
         self
@@ -208,6 +988,23 @@ impl<'a, C, A> Builder<'a, Token<C, A>> {
         self
     }
 
+    /// Returns the `filter_level` set with [`filter_level`](Builder::filter_level).
+    ///
+    /// The `get_` prefix is needed because the setter already has the name `filter_level`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use twitter_stream::builder::FilterLevel;
+    ///
+    /// # let token = twitter_stream::Token::from_parts("", "", "", "");
+    /// let builder = twitter_stream::Builder::new(token).with_filter_level(FilterLevel::Low);
+    /// assert_eq!(builder.get_filter_level(), Some(&FilterLevel::Low));
+    /// ```
+    pub fn get_filter_level(&self) -> Option<&FilterLevel> {
+        self.parameters.filter_level.as_ref()
+    }
+
     /// Set a comma-separated language identifiers to receive Tweets
     /// written in the specified languages only.
     ///
@@ -221,6 +1018,64 @@ impl<'a, C, A> Builder<'a, Token<C, A>> {
         self
     }
 
+    /// Set a list of languages to receive Tweets written in the specified languages only.
+    ///
+    /// This is a typed alternative to [`language`](Builder::language).
+    /// Setting an empty slice will unset this parameter.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use twitter_stream::builder::Language;
+    ///
+    /// # let token = twitter_stream::Token::from_parts("", "", "", "");
+    /// twitter_stream::Builder::new(token)
+    ///     .track("@Twitter")
+    ///     .language_enum(&[Language::English, Language::Custom("zh-tw".into())]);
+    /// ```
+    pub fn language_enum(&mut self, languages: &[Language<'_>]) -> &mut Self {
+        let languages = languages.iter().map(AsRef::as_ref).collect::<Vec<_>>();
+        self.parameters.language = languages.join(",").into();
+        self
+    }
+
+    /// Set a list of BCP 47 language identifiers to receive Tweets written in the specified
+    /// languages only.
+    ///
+    /// This is an alternative to [`language`](Builder::language) which joins the identifiers
+    /// with commas. Empty identifiers are skipped, and setting a slice without any non-empty
+    /// identifier will unset this parameter.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParameterError::InvalidLanguage`] without changing the parameter if any of
+    /// the identifiers contains a character other than ASCII alphanumerics and hyphens.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let token = twitter_stream::Token::from_parts("", "", "", "");
+    /// twitter_stream::Builder::new(token)
+    ///     .track("@Twitter")
+    ///     .languages(&["en", "zh-tw"])
+    ///     .unwrap();
+    /// ```
+    pub fn languages(&mut self, languages: &[&str]) -> Result<&mut Self, ParameterError> {
+        if let Some(lang) = languages
+            .iter()
+            .find(|lang| !lang.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-'))
+        {
+            return Err(ParameterError::InvalidLanguage((*lang).to_owned()));
+        }
+        let languages = languages
+            .iter()
+            .filter(|lang| !lang.is_empty())
+            .copied()
+            .collect::<Vec<_>>();
+        self.parameters.language = languages.join(",").into();
+        Ok(self)
+    }
+
     /// Set a list of user IDs to receive Tweets from the specified users.
     ///
     /// Setting an empty slice will unset this parameter.
@@ -233,7 +1088,113 @@ impl<'a, C, A> Builder<'a, Token<C, A>> {
         self
     }
 
-    /// A comma separated list of phrases to filter Tweets by.
+    /// Resolves `screen_names` to user IDs and returns a builder with the `follow` parameter set
+    /// to the IDs, with the other parameters cloned.
+    ///
+    /// The `follow` parameter only accepts numeric user IDs. This method looks them up with
+    /// the [`GET users/lookup`][1] endpoint of the Twitter API using `client` and the token of
+    /// the builder. A leading `@` of a screen name is ignored. Unknown or suspended users are
+    /// omitted from the result.
+    ///
+    /// The endpoint accepts up to 100 screen names per request, so a request is made for every
+    /// 100 screen names. The endpoint is rate limited to 900 requests per 15 minutes with user
+    /// authentication and 300 requests per 15 minutes with application-only authentication.
+    ///
+    /// [1]: https://developer.twitter.com/en/docs/twitter-api/v1/accounts-and-users/follow-search-get-users/api-reference/get-users-lookup
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn doc() -> Result<(), twitter_stream::hyper::Error> {
+    /// # let token = twitter_stream::Token::from_parts("", "", "", "");
+    /// let conn = hyper_tls::HttpsConnector::new();
+    /// let client = hyper_pkg::Client::builder().build::<_, hyper_pkg::Body>(conn);
+    ///
+    /// let stream = twitter_stream::Builder::new(token)
+    ///     .follow_screen_names(client.clone(), &["@Twitter", "TwitterDev"])
+    ///     .await?
+    ///     .listen_with_client(client)
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub async fn follow_screen_names<S, B>(
+        &self,
+        mut client: S,
+        screen_names: &[&str],
+    ) -> Result<Builder<'a, T>, crate::Error<S::Error>>
+    where
+        S: HttpService<B>,
+        S::ResponseBody: http_body::Body<Error = S::Error>,
+        B: From<Vec<u8>>,
+        T: Authorize + Clone,
+    {
+        let ids = lookup::user_ids(&mut client, &self.token, screen_names).await?;
+        let mut builder = self.clone();
+        builder.parameters.follow = ids.into();
+        Ok(builder)
+    }
+
+    /// Splits the `follow` parameter into chunks of at most `chunk_size` user IDs, returning
+    /// a builder for each chunk with the other parameters cloned.
+    ///
+    /// This is useful for following more users than a single connection allows by opening
+    /// a connection for each builder. `chunk_size` is capped at 5,000, the maximum number of
+    /// user IDs allowed for a connection. If `follow` is empty, a single builder is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let token = twitter_stream::Token::from_parts("", "", "", "");
+    /// let follow = (0..12_000).collect::<Vec<u64>>();
+    /// let builders = twitter_stream::Builder::new(token)
+    ///     .follow(&*follow)
+    ///     .split_follow(5_000);
+    /// assert_eq!(builders.len(), 3);
+    /// ```
+    pub fn split_follow(&self, chunk_size: usize) -> Vec<Self>
+    where
+        T: Clone,
+    {
+        assert!(chunk_size != 0, "chunk size must be non-zero");
+        let chunk_size = chunk_size.min(ParameterError::MAX_FOLLOW);
+
+        if self.parameters.follow.is_empty() {
+            return vec![self.clone()];
+        }
+
+        let with_follow = |follow: Cow<'a, [u64]>| {
+            let mut builder = Builder {
+                token: self.token.clone(),
+                endpoint: self.endpoint.clone(),
+                parameters: self.parameters.clone(),
+                user_agent: self.user_agent.clone(),
+                oauth: self.oauth,
+                config: self.config.clone(),
+            };
+            builder.parameters.follow = follow;
+            builder
+        };
+
+        match self.parameters.follow {
+            Cow::Borrowed(follow) => follow
+                .chunks(chunk_size)
+                .map(|chunk| with_follow(Cow::Borrowed(chunk)))
+                .collect(),
+            Cow::Owned(ref follow) => follow
+                .chunks(chunk_size)
+                .map(|chunk| with_follow(Cow::Owned(chunk.to_vec())))
+                .collect(),
+        }
+    }
+
+    /// A comma separated list of phrases to filter Tweets by.
     ///
     /// Setting an empty string will unset this parameter.
     ///
@@ -245,6 +1206,38 @@ impl<'a, C, A> Builder<'a, Token<C, A>> {
         self
     }
 
+    /// Set a list of phrases to filter Tweets by.
+    ///
+    /// This is an alternative to [`track`](Builder::track) which joins the phrases with commas.
+    /// A Tweet matches if it matches any of the phrases, and it matches a phrase if it contains
+    /// all of the space-separated terms of the phrase. For example, `&["foo bar", "baz"]` matches
+    /// Tweets containing both `foo` and `bar`, or `baz`.
+    ///
+    /// Setting an empty slice will unset this parameter.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParameterError::InvalidPhrase`] without changing the parameter if any of the
+    /// phrases contains a comma, which would split the phrase into separate phrases, or is empty
+    /// or consists only of whitespace, which would make an empty phrase.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let token = twitter_stream::Token::from_parts("", "", "", "");
+    /// twitter_stream::Builder::new(token)
+    ///     .track_phrases(&["@Twitter", "Rust lang"])
+    ///     .unwrap();
+    /// ```
+    pub fn track_phrases(&mut self, phrases: &[&str]) -> Result<&mut Self, ParameterError> {
+        let invalid = |phrase: &&&str| phrase.contains(',') || phrase.trim().is_empty();
+        if let Some(phrase) = phrases.iter().find(invalid) {
+            return Err(ParameterError::InvalidPhrase((*phrase).to_owned()));
+        }
+        self.parameters.track = phrases.join(",").into();
+        Ok(self)
+    }
+
     /// Set a list of bounding boxes to filter Tweets by.
     ///
     /// Setting an empty slice will unset this parameter.
@@ -258,6 +1251,31 @@ impl<'a, C, A> Builder<'a, Token<C, A>> {
         self
     }
 
+    /// Set a list of bounding boxes given as tuples of
+    /// `(west_longitude, south_latitude, east_longitude, north_latitude)`.
+    ///
+    /// This is a shorthand for [`locations`](Builder::locations) which converts each tuple into
+    /// a [`BoundingBox`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use twitter_stream::builder::BoundingBox;
+    ///
+    /// # let token = twitter_stream::Token::from_parts("", "", "", "");
+    /// let req = twitter_stream::Builder::new(token.clone())
+    ///     .locations_tuples(&[(-122.75, 36.8, -121.75, 37.8)])
+    ///     .build_request();
+    /// let expected = twitter_stream::Builder::new(token)
+    ///     .locations(vec![BoundingBox::new(-122.75, 36.8, -121.75, 37.8)])
+    ///     .build_request();
+    /// assert_eq!(req.body(), expected.body());
+    /// ```
+    pub fn locations_tuples(&mut self, locations: &[(f64, f64, f64, f64)]) -> &mut Self {
+        let locations = locations.iter().copied().map(BoundingBox::from);
+        self.locations(locations.collect::<Vec<_>>())
+    }
+
     /// The `count` parameter.
     /// This parameter requires elevated access to use.
     ///
@@ -268,6 +1286,493 @@ impl<'a, C, A> Builder<'a, Token<C, A>> {
         self.parameters.count = count.into();
         self
     }
+
+    /// Set whether to receive the replies to the followed users from the users you do not follow
+    /// (`replies=all`).
+    ///
+    /// This parameter was taken by the deprecated user streams, and is still honored by some
+    /// compatible endpoints set with [`endpoint`](Builder::endpoint).
+    pub fn replies(&mut self, replies: bool) -> &mut Self {
+        self.parameters.replies = replies;
+        self
+    }
+
+    /// Set the `with` parameter, which scopes the accounts whose activities are delivered.
+    ///
+    /// This parameter was taken by the deprecated user streams, and is still honored by some
+    /// compatible endpoints set with [`endpoint`](Builder::endpoint).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use twitter_stream::builder::{RequestMethod, StreamScope, Uri};
+    ///
+    /// # let token = twitter_stream::Token::from_parts("", "", "", "");
+    /// let endpoint = Uri::from_static("https://userstream.example.com/1.1/user.json");
+    /// twitter_stream::Builder::new(token)
+    ///     .endpoint((RequestMethod::GET, endpoint))
+    ///     .stream_scope(StreamScope::Followings);
+    /// ```
+    pub fn stream_scope(&mut self, scope: impl Into<Option<StreamScope<'a>>>) -> &mut Self {
+        self.parameters.with = scope.into();
+        self
+    }
+
+    /// Set the initial capacity in bytes of the buffer used to assemble a message that is split
+    /// across multiple chunks of the response body.
+    ///
+    /// The buffer grows as needed, so this is only an optimization which reduces reallocations
+    /// when the messages are large, e.g. Tweets with full entities, which are often several
+    /// kilobytes long. Defaults to `0`, which allocates the buffer lazily.
+    pub fn line_buffer_capacity(&mut self, capacity: usize) -> &mut Self {
+        self.config.line_buffer_capacity = capacity;
+        self
+    }
+
+    /// Set the maximum length in bytes of a message, beyond which the stream fails with
+    /// [`Error::LineTooLong`](crate::Error::LineTooLong) and ends.
+    ///
+    /// The length does not include the terminating CRLF, and the limit applies regardless of
+    /// how the message is split into the chunks of the response body.
+    ///
+    /// Without the limit, the stream keeps buffering the data until it finds the end of
+    /// the message, so a misbehaving server (or a proxy) that never sends a CRLF can exhaust
+    /// the memory. Tweets are usually well under 64 KiB. Defaults to `None`, i.e. no limit.
+    ///
+    /// In `delimited` mode, a length prefix exceeding the limit is rejected before reading
+    /// the message.
+    pub fn max_line_length(&mut self, max_line_length: impl Into<Option<usize>>) -> &mut Self {
+        self.config.max_line_length = max_line_length.into();
+        self
+    }
+
+    /// Set whether to skip the messages that are not valid UTF-8 instead of yielding
+    /// [`Error::Utf8`](crate::Error::Utf8).
+    ///
+    /// With the `json` feature, this also makes [`TwitterStream::parse`] skip the messages that
+    /// fail to deserialize instead of yielding [`Error::Json`].
+    ///
+    /// This keeps a long-running consumer going over a malformed message, at the cost of
+    /// silently losing it.
+    ///
+    /// [`TwitterStream::parse`]: crate::TwitterStream::parse
+    /// [`Error::Json`]: crate::Error::Json
+    #[cfg_attr(not(feature = "json"), allow(broken_intra_doc_links))]
+    pub fn skip_invalid(&mut self, skip_invalid: bool) -> &mut Self {
+        self.config.skip_invalid = skip_invalid;
+        self
+    }
+
+    /// Set whether to fail with [`Error::Gzip`](crate::Error::Gzip) if the response from
+    /// the Streaming API is not gzip-encoded.
+    ///
+    /// `Builder` always requests the `gzip` encoding with the `gzip` feature, but the server may
+    /// respond with an unencoded body. Setting this makes sure the bandwidth saving of
+    /// the compression is actually in effect.
+    #[cfg(feature = "gzip")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gzip")))]
+    pub fn require_gzip(&mut self, require_gzip: bool) -> &mut Self {
+        self.config.require_gzip = require_gzip;
+        self
+    }
+
+    /// Set whether to yield a [disconnect message][1] from the Streaming API as
+    /// [`Error::Disconnect`](crate::Error::Disconnect) instead of a JSON string.
+    ///
+    /// The Streaming API sends a disconnect message right before closing the connection.
+    /// The [`DisconnectCode`](crate::error::DisconnectCode) in the error tells you the reason
+    /// of the disconnection, which helps you to decide whether to reconnect.
+    ///
+    /// [1]: https://developer.twitter.com/en/docs/tweets/filter-realtime/guides/streaming-message-types
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn surface_disconnect(&mut self, surface_disconnect: bool) -> &mut Self {
+        self.config.surface_disconnect = surface_disconnect;
+        self
+    }
+
+    /// Set a callback to be called with a [stall warning][1] when the Streaming API sends one.
+    ///
+    /// This also sets the `stall_warnings` parameter, without which the Streaming API does not
+    /// send the warnings. The warning message is still yielded from the stream as a JSON string
+    /// after the callback returns.
+    ///
+    /// A warning tells that the client is falling behind and will be disconnected when
+    /// its queue on the server is full, so you can react to it, e.g. by shedding load,
+    /// before being disconnected.
+    ///
+    /// [1]: https://developer.twitter.com/en/docs/tweets/filter-realtime/guides/streaming-message-types
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # let token = twitter_stream::Token::from_parts("", "", "", "");
+    /// twitter_stream::Builder::new(token)
+    ///     .track("@Twitter")
+    ///     .on_warning(|warning| {
+    ///         eprintln!("{}: {}", warning.code, warning.message);
+    ///     });
+    /// ```
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn on_warning<F>(&mut self, on_warning: F) -> &mut Self
+    where
+        F: FnMut(crate::error::Warning) + Send + 'static,
+    {
+        self.parameters.stall_warnings = true;
+        self.config.on_warning = Some(WarningCallback(Arc::new(Mutex::new(on_warning))));
+        self
+    }
+
+    /// Set a duration after which the stream fails with
+    /// [`Error::TimedOut`](crate::Error::TimedOut) if no data is received from the Streaming API.
+    ///
+    /// The timer is reset whenever any data is received, including the blank lines that the
+    /// Streaming API sends every 30 seconds as a keep-alive signal. Twitter recommends
+    /// a timeout of 90 seconds.
+    ///
+    /// The stream must be polled within the context of a Tokio runtime with the time driver
+    /// enabled.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn idle_timeout(&mut self, idle_timeout: impl Into<Option<Duration>>) -> &mut Self {
+        self.config.idle_timeout = idle_timeout.into();
+        self
+    }
+
+    /// Set a duration after which the `FutureTwitterStream` fails with
+    /// [`Error::TimedOut`](crate::Error::TimedOut) if the response headers have not arrived.
+    ///
+    /// This covers establishing the connection and the TLS handshake as well as waiting for
+    /// the response, and is independent of [`idle_timeout`](Builder::idle_timeout), which only
+    /// applies after the connection is established. The duration is counted from the first
+    /// time the future is polled.
+    ///
+    /// The future must be polled within the context of a Tokio runtime with the time driver
+    /// enabled.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn connect_timeout(&mut self, connect_timeout: impl Into<Option<Duration>>) -> &mut Self {
+        self.config.connect_timeout = connect_timeout.into();
+        self
+    }
+
+    /// Set a [`RateLimitCounter`] to count the consecutive rate-limited connection attempts made
+    /// with this `Builder`.
+    ///
+    /// Passing `None` will unset this option, in which case every rate-limited attempt reports
+    /// the initial back-off.
+    pub fn rate_limit_counter(
+        &mut self,
+        counter: impl Into<Option<RateLimitCounter>>,
+    ) -> &mut Self {
+        self.config.rate_limit_counter = counter.into();
+        self
+    }
+
+    /// Set a comma-separated list of the Tweet fields to be included in the Tweet objects
+    /// returned from the v2 filtered stream (the `tweet.fields` parameter).
+    ///
+    /// Setting an empty string will unset this parameter.
+    ///
+    /// See the [Twitter Developer Documentation][1] for more information.
+    ///
+    /// [1]: https://developer.twitter.com/en/docs/twitter-api/fields
+    pub fn tweet_fields(&mut self, tweet_fields: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.parameters.tweet_fields = tweet_fields.into();
+        self
+    }
+
+    /// Set a comma-separated list of the objects to be expanded in the responses
+    /// from the v2 filtered stream.
+    ///
+    /// Setting an empty string will unset this parameter.
+    ///
+    /// See the [Twitter Developer Documentation][1] for more information.
+    ///
+    /// [1]: https://developer.twitter.com/en/docs/twitter-api/expansions
+    pub fn expansions(&mut self, expansions: impl Into<Cow<'a, str>>) -> &mut Self {
+        self.parameters.expansions = expansions.into();
+        self
+    }
+
+    /// Set the number of minutes of Tweets to be delivered from before the connection
+    /// to the v2 filtered stream, to recover the Tweets missed during a disconnection.
+    /// This parameter requires Academic Research access to use.
+    ///
+    /// See the [Twitter Developer Documentation][1] for more information.
+    ///
+    /// [1]: https://developer.twitter.com/en/docs/twitter-api/tweets/filtered-stream/integrate/recovery-and-redundancy-features
+    pub fn backfill_minutes(&mut self, backfill_minutes: impl Into<Option<u32>>) -> &mut Self {
+        self.parameters.backfill_minutes = backfill_minutes.into();
+        self
+    }
+}
+
+/// Consuming versions of the setters, for keeping a configured `Builder` built in a single
+/// expression.
+///
+/// Every setter returning `&mut Self` has a consuming `with_*` version here. The setters
+/// validating their arguments ([`endpoint_str`](Builder::endpoint_str), [`param`](Builder::param),
+/// [`languages`](Builder::languages), [`track_phrases`](Builder::track_phrases) and
+/// [`powertrack`](Builder::powertrack)) and the shorthands
+/// [`for_sample`](Builder::for_sample) and [`for_filter`](Builder::for_filter) do not have one.
+impl<'a, T> Builder<'a, T> {
+    /// Same as [`endpoint`](Builder::endpoint) except that it takes and returns the `Builder` by
+    /// value.
+    pub fn with_endpoint(mut self, endpoint: impl Into<Option<(RequestMethod, Uri)>>) -> Self {
+        self.endpoint(endpoint);
+        self
+    }
+
+    /// Same as [`user_agent`](Builder::user_agent) except that it takes and returns the `Builder`
+    /// by value.
+    pub fn with_user_agent(mut self, user_agent: impl Into<HeaderValue>) -> Self {
+        self.user_agent(user_agent);
+        self
+    }
+
+    /// Same as [`oauth_nonce`](Builder::oauth_nonce) except that it takes and returns the `Builder`
+    /// by value.
+    pub fn with_oauth_nonce(mut self, nonce: impl Into<Option<&'a str>>) -> Self {
+        self.oauth_nonce(nonce);
+        self
+    }
+
+    /// Same as [`oauth_timestamp`](Builder::oauth_timestamp) except that it takes and returns the
+    /// `Builder` by value.
+    pub fn with_oauth_timestamp(mut self, timestamp: impl Into<Option<u64>>) -> Self {
+        self.oauth_timestamp(timestamp);
+        self
+    }
+
+    /// Same as [`token`](Builder::token) except that it takes and returns the `Builder` by value.
+    pub fn with_token(mut self, token: T) -> Self {
+        self.token(token);
+        self
+    }
+
+    /// Same as [`delimited`](Builder::delimited) except that it takes and returns the `Builder` by
+    /// value.
+    pub fn with_delimited(mut self, delimited: bool) -> Self {
+        self.delimited(delimited);
+        self
+    }
+
+    /// Same as [`stall_warnings`](Builder::stall_warnings) except that it takes and returns the
+    /// `Builder` by value.
+    pub fn with_stall_warnings(mut self, stall_warnings: bool) -> Self {
+        self.stall_warnings(stall_warnings);
+        self
+    }
+
+    /// Same as [`filter_level`](Builder::filter_level) except that it takes and returns the
+    /// `Builder` by value.
+    pub fn with_filter_level(mut self, filter_level: impl Into<Option<FilterLevel>>) -> Self {
+        self.filter_level(filter_level);
+        self
+    }
+
+    /// Same as [`language`](Builder::language) except that it takes and returns the `Builder` by
+    /// value.
+    pub fn with_language(mut self, language: impl Into<Cow<'a, str>>) -> Self {
+        self.language(language);
+        self
+    }
+
+    /// Same as [`language_enum`](Builder::language_enum) except that it takes and returns the
+    /// `Builder` by value.
+    pub fn with_language_enum(mut self, languages: &[Language<'_>]) -> Self {
+        self.language_enum(languages);
+        self
+    }
+
+    /// Same as [`follow`](Builder::follow) except that it takes and returns the `Builder` by value.
+    pub fn with_follow(mut self, follow: impl Into<Cow<'a, [u64]>>) -> Self {
+        self.follow(follow);
+        self
+    }
+
+    /// Same as [`track`](Builder::track) except that it takes and returns the `Builder` by value.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use twitter_stream::builder::FilterLevel;
+    ///
+    /// # let token = twitter_stream::Token::from_parts("", "", "", "");
+    /// let builder = twitter_stream::Builder::new(token)
+    ///     .with_track("@Twitter")
+    ///     .with_language("en")
+    ///     .with_filter_level(FilterLevel::Low);
+    /// # drop(builder);
+    /// ```
+    pub fn with_track(mut self, track: impl Into<Cow<'a, str>>) -> Self {
+        self.track(track);
+        self
+    }
+
+    /// Same as [`locations`](Builder::locations) except that it takes and returns the `Builder` by
+    /// value.
+    pub fn with_locations(mut self, locations: impl Into<Cow<'a, [BoundingBox]>>) -> Self {
+        self.locations(locations);
+        self
+    }
+
+    /// Same as [`locations_tuples`](Builder::locations_tuples) except that it takes and returns the
+    /// `Builder` by value.
+    pub fn with_locations_tuples(mut self, locations: &[(f64, f64, f64, f64)]) -> Self {
+        self.locations_tuples(locations);
+        self
+    }
+
+    /// Same as [`count`](Builder::count) except that it takes and returns the `Builder` by value.
+    pub fn with_count(mut self, count: impl Into<Option<i32>>) -> Self {
+        self.count(count);
+        self
+    }
+
+    /// Same as [`replies`](Builder::replies) except that it takes and returns the `Builder` by
+    /// value.
+    pub fn with_replies(mut self, replies: bool) -> Self {
+        self.replies(replies);
+        self
+    }
+
+    /// Same as [`stream_scope`](Builder::stream_scope) except that it takes and returns the
+    /// `Builder` by value.
+    pub fn with_stream_scope(mut self, scope: impl Into<Option<StreamScope<'a>>>) -> Self {
+        self.stream_scope(scope);
+        self
+    }
+
+    /// Same as [`line_buffer_capacity`](Builder::line_buffer_capacity) except that it takes and
+    /// returns the `Builder` by value.
+    pub fn with_line_buffer_capacity(mut self, capacity: usize) -> Self {
+        self.line_buffer_capacity(capacity);
+        self
+    }
+
+    /// Same as [`max_line_length`](Builder::max_line_length) except that it takes and returns the
+    /// `Builder` by value.
+    pub fn with_max_line_length(mut self, max_line_length: impl Into<Option<usize>>) -> Self {
+        self.max_line_length(max_line_length);
+        self
+    }
+
+    /// Same as [`skip_invalid`](Builder::skip_invalid) except that it takes and returns the
+    /// `Builder` by value.
+    pub fn with_skip_invalid(mut self, skip_invalid: bool) -> Self {
+        self.skip_invalid(skip_invalid);
+        self
+    }
+
+    /// Same as [`require_gzip`](Builder::require_gzip) except that it takes and returns the
+    /// `Builder` by value.
+    #[cfg(feature = "gzip")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gzip")))]
+    pub fn with_require_gzip(mut self, require_gzip: bool) -> Self {
+        self.require_gzip(require_gzip);
+        self
+    }
+
+    /// Same as [`surface_disconnect`](Builder::surface_disconnect) except that it takes and returns
+    /// the `Builder` by value.
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn with_surface_disconnect(mut self, surface_disconnect: bool) -> Self {
+        self.surface_disconnect(surface_disconnect);
+        self
+    }
+
+    /// Same as [`idle_timeout`](Builder::idle_timeout) except that it takes and returns the
+    /// `Builder` by value.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn with_idle_timeout(mut self, idle_timeout: impl Into<Option<Duration>>) -> Self {
+        self.idle_timeout(idle_timeout);
+        self
+    }
+
+    /// Same as [`connect_timeout`](Builder::connect_timeout) except that it takes and returns the
+    /// `Builder` by value.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn with_connect_timeout(mut self, connect_timeout: impl Into<Option<Duration>>) -> Self {
+        self.connect_timeout(connect_timeout);
+        self
+    }
+
+    /// Same as [`rate_limit_counter`](Builder::rate_limit_counter) except that it takes and
+    /// returns the `Builder` by value.
+    pub fn with_rate_limit_counter(mut self, counter: impl Into<Option<RateLimitCounter>>) -> Self {
+        self.rate_limit_counter(counter);
+        self
+    }
+
+    /// Same as [`tweet_fields`](Builder::tweet_fields) except that it takes and returns the
+    /// `Builder` by value.
+    pub fn with_tweet_fields(mut self, tweet_fields: impl Into<Cow<'a, str>>) -> Self {
+        self.tweet_fields(tweet_fields);
+        self
+    }
+
+    /// Same as [`expansions`](Builder::expansions) except that it takes and returns the `Builder`
+    /// by value.
+    pub fn with_expansions(mut self, expansions: impl Into<Cow<'a, str>>) -> Self {
+        self.expansions(expansions);
+        self
+    }
+
+    /// Same as [`backfill_minutes`](Builder::backfill_minutes) except that it takes and returns the
+    /// `Builder` by value.
+    pub fn with_backfill_minutes(mut self, backfill_minutes: impl Into<Option<u32>>) -> Self {
+        self.backfill_minutes(backfill_minutes);
+        self
+    }
+
+    /// Same as [`http2_only`](Builder::http2_only) except that it takes and returns the `Builder`
+    /// by value.
+    #[cfg(feature = "http2")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "http2")))]
+    pub fn with_http2_only(mut self, http2_only: bool) -> Self
+    where
+        T: Authorize,
+    {
+        self.http2_only(http2_only);
+        self
+    }
+
+    /// Same as [`on_warning`](Builder::on_warning) except that it takes and returns the `Builder`
+    /// by value.
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn with_on_warning<F>(mut self, on_warning: F) -> Self
+    where
+        F: FnMut(crate::error::Warning) + Send + 'static,
+    {
+        self.on_warning(on_warning);
+        self
+    }
+}
+
+impl std::fmt::Display for DryRun {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        /// Quotes `s` for a POSIX shell.
+        fn quote(s: &str, f: &mut Formatter<'_>) -> fmt::Result {
+            write!(f, "'{}'", s.replace('\'', r"'\''"))
+        }
+
+        write!(f, "curl -X {} ", self.method)?;
+        quote(&self.uri, f)?;
+        f.write_str(" -H ")?;
+        quote(&format!("Authorization: {}", self.authorization), f)?;
+        if !self.body.is_empty() {
+            f.write_str(" -d ")?;
+            quote(&self.body, f)?;
+        }
+        Ok(())
+    }
 }
 
 impl std::default::Default for FilterLevel {
@@ -282,13 +1787,186 @@ impl std::fmt::Display for FilterLevel {
     }
 }
 
-fn prepare_request(
+impl std::str::FromStr for FilterLevel {
+    type Err = ParseFilterLevelError;
+
+    /// Parses a `FilterLevel` from its parameter value (`"none"`, `"low"` or `"medium"`).
+    fn from_str(s: &str) -> Result<Self, ParseFilterLevelError> {
+        match s {
+            "none" => Ok(FilterLevel::None),
+            "low" => Ok(FilterLevel::Low),
+            "medium" => Ok(FilterLevel::Medium),
+            _ => Err(ParseFilterLevelError(())),
+        }
+    }
+}
+
+impl AsRef<str> for StreamScope<'_> {
+    fn as_ref(&self) -> &str {
+        match *self {
+            StreamScope::User => "user",
+            StreamScope::Followings => "followings",
+            StreamScope::Custom(ref value) => value,
+        }
+    }
+}
+
+impl fmt::Display for StreamScope<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.as_ref().fmt(f)
+    }
+}
+
+/// An error returned when parsing an unknown string as a [`FilterLevel`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseFilterLevelError(());
+
+impl std::fmt::Display for ParseFilterLevelError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("unknown filter level")
+    }
+}
+
+impl std::error::Error for ParseFilterLevelError {}
+
+impl<T: Authorize> PartialEq for Builder<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.token.token_eq(&other.token)
+            && self.endpoint == other.endpoint
+            && self.parameters == other.parameters
+            && self.user_agent == other.user_agent
+            && self.oauth == other.oauth
+            && self.config == other.config
+    }
+}
+
+impl<T: Authorize> Eq for Builder<'_, T> {}
+
+// Compares the `locations` bitwise to keep the comparison reflexive in the presence of `NaN`.
+impl PartialEq for Parameters<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        // Destructure `self` so that a new field cannot be forgotten here.
+        let Parameters {
+            backfill_minutes,
+            expansions,
+            delimited,
+            stall_warnings,
+            filter_level,
+            language,
+            follow,
+            track,
+            locations,
+            count,
+            replies,
+            tweet_fields,
+            with,
+            extra,
+        } = self;
+        let locations_eq = {
+            let a = BoundingBox::flatten_slice(locations).flat();
+            let b = BoundingBox::flatten_slice(&other.locations).flat();
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.to_bits() == b.to_bits())
+        };
+        *backfill_minutes == other.backfill_minutes
+            && *expansions == other.expansions
+            && *delimited == other.delimited
+            && *stall_warnings == other.stall_warnings
+            && *filter_level == other.filter_level
+            && *language == other.language
+            && *follow == other.follow
+            && *track == other.track
+            && locations_eq
+            && *count == other.count
+            && *replies == other.replies
+            && *tweet_fields == other.tweet_fields
+            && *with == other.with
+            && *extra == other.extra
+    }
+}
+
+impl Eq for Parameters<'_> {}
+
+#[cfg(feature = "json")]
+impl WarningCallback {
+    pub(crate) fn call(&self, warning: crate::error::Warning) {
+        // Keep calling the callback even if it has panicked while holding the lock.
+        let mut f = self.0.lock().unwrap_or_else(|e| e.into_inner());
+        f(warning);
+    }
+}
+
+#[cfg(feature = "json")]
+impl PartialEq for WarningCallback {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[cfg(feature = "json")]
+impl fmt::Debug for WarningCallback {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("WarningCallback").finish()
+    }
+}
+
+impl RateLimitCounter {
+    /// Creates a counter starting from zero.
+    pub fn new() -> Self {
+        RateLimitCounter::default()
+    }
+
+    /// Returns the number of consecutive rate-limited attempts counted so far.
+    pub fn attempts(&self) -> u32 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Increments the count, returning the previous value.
+    pub(crate) fn increment(&self) -> u32 {
+        self.0.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Resets the count to zero.
+    pub fn reset(&self) {
+        self.0.store(0, Ordering::Relaxed);
+    }
+}
+
+impl PartialEq for RateLimitCounter {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for RateLimitCounter {}
+
+impl Parameters<'_> {
+    /// Checks the parameters against the limits of the Streaming API.
+    fn validate(&self) -> Result<(), ParameterError> {
+        if self.follow.len() > ParameterError::MAX_FOLLOW {
+            return Err(ParameterError::TooManyFollow(self.follow.len()));
+        }
+        if !self.track.is_empty() {
+            let track = self.track.split(',').count();
+            if track > ParameterError::MAX_TRACK {
+                return Err(ParameterError::TooManyTrack(track));
+            }
+        }
+        if self.locations.len() > ParameterError::MAX_LOCATIONS {
+            return Err(ParameterError::TooManyLocations(self.locations.len()));
+        }
+        Ok(())
+    }
+}
+
+fn prepare_request<T: Authorize>(
     endpoint: Option<&(RequestMethod, Uri)>,
-    token: Token<&str, &str>,
+    token: &T,
     parameters: &Parameters<'_>,
+    user_agent: &HeaderValue,
+    oauth: &OAuthOptions<'_>,
 ) -> http::Request<Vec<u8>> {
     let uri;
-    let (method, endpoint) = if let Some(&(ref method, ref endpoint)) = endpoint {
+    let (method, endpoint) = if let Some((method, endpoint)) = endpoint {
         (method, endpoint)
     } else if parameters.follow.is_empty()
         && parameters.track.is_empty()
@@ -301,13 +1979,15 @@ fn prepare_request(
         (&RequestMethod::POST, &uri)
     };
 
-    let req = Request::builder().method(method.clone());
-
-    let mut oauth = oauth::Builder::new(token.client.as_ref(), oauth::HmacSha1);
-    oauth.token(token.token.as_ref());
+    let req = Request::builder()
+        .method(method.clone())
+        .header(USER_AGENT, user_agent.clone());
+    #[cfg(feature = "gzip")]
+    let req = req.header(ACCEPT_ENCODING, HeaderValue::from_static("gzip"));
+    let parameters = &extra::WithExtra(parameters);
+    let authorization = token.authorization(method, endpoint, parameters, oauth);
 
     if RequestMethod::POST == method {
-        let authorization = oauth.post(endpoint, parameters);
         let data = oauth::to_form_urlencoded(parameters);
 
         req.uri(endpoint.clone())
@@ -320,7 +2000,6 @@ fn prepare_request(
             .body(data.into_bytes())
             .unwrap()
     } else {
-        let authorization = oauth.build(method.as_ref(), endpoint, parameters);
         let uri = oauth::to_uri_query(endpoint.to_string(), parameters);
 
         req.uri(uri)
@@ -332,6 +2011,29 @@ fn prepare_request(
 
 const COMMA: &str = "%2C";
 
+fn fmt_delimited(_: &bool, f: &mut Formatter<'_>) -> fmt::Result {
+    f.write_str("length")
+}
+
+/// Decodes a component of an `application/x-www-form-urlencoded` query string.
+fn decode_component(s: &str) -> String {
+    let s = s.replace('+', " ");
+    percent_encoding::percent_decode_str(&s)
+        .decode_utf8_lossy()
+        .into_owned()
+}
+
+/// Checks that `s` is non-empty and consists of unreserved characters of RFC 3986 only.
+fn is_unreserved(s: &str) -> bool {
+    !s.is_empty()
+        && s.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"-._~".contains(&b))
+}
+
+fn fmt_replies(_: &bool, f: &mut Formatter<'_>) -> fmt::Result {
+    f.write_str("all")
+}
+
 fn fmt_follow(ids: &[u64], f: &mut Formatter<'_>) -> fmt::Result {
     fmt_join(ids, COMMA, f)
 }
@@ -344,3 +2046,759 @@ fn fmt_locations(locs: &[BoundingBox], f: &mut Formatter<'_>) -> fmt::Result {
 fn not(p: &bool) -> bool {
     !p
 }
+
+mod private {
+    use std::num::NonZeroU64;
+
+    use http::Uri;
+
+    use super::RequestMethod;
+    use crate::{Auth, BearerToken, Token};
+
+    /// Options overriding the `oauth_*` parameters of the OAuth 1.0a signature.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct OAuthOptions<'a> {
+        pub nonce: Option<&'a str>,
+        pub timestamp: Option<NonZeroU64>,
+    }
+
+    pub trait Sealed {
+        /// Returns the value of the `Authorization` header for the request.
+        fn authorization<R: oauth::Request>(
+            &self,
+            method: &RequestMethod,
+            uri: &Uri,
+            request: &R,
+            options: &OAuthOptions<'_>,
+        ) -> String;
+
+        /// Returns `true` if `self` and `other` are the same credentials.
+        fn token_eq(&self, other: &Self) -> bool;
+    }
+
+    impl<C: AsRef<str>, A: AsRef<str>> Sealed for Token<C, A> {
+        fn authorization<R: oauth::Request>(
+            &self,
+            method: &RequestMethod,
+            uri: &Uri,
+            request: &R,
+            options: &OAuthOptions<'_>,
+        ) -> String {
+            let mut oauth = oauth::Builder::new(self.client.as_ref(), oauth::HmacSha1);
+            oauth
+                .token(self.token.as_ref())
+                .nonce(options.nonce)
+                .timestamp(options.timestamp);
+            oauth.build(method.as_str(), uri, request)
+        }
+
+        fn token_eq(&self, other: &Self) -> bool {
+            self.client.identifier.as_ref() == other.client.identifier.as_ref()
+                && self.client.secret.as_ref() == other.client.secret.as_ref()
+                && self.token.identifier.as_ref() == other.token.identifier.as_ref()
+                && self.token.secret.as_ref() == other.token.secret.as_ref()
+        }
+    }
+
+    impl<T: AsRef<str>> Sealed for BearerToken<T> {
+        fn authorization<R: oauth::Request>(
+            &self,
+            _: &RequestMethod,
+            _: &Uri,
+            _: &R,
+            _: &OAuthOptions<'_>,
+        ) -> String {
+            format!("Bearer {}", self.0.as_ref())
+        }
+
+        fn token_eq(&self, other: &Self) -> bool {
+            self.0.as_ref() == other.0.as_ref()
+        }
+    }
+
+    impl<T: AsRef<str>> Sealed for Auth<T> {
+        fn authorization<R: oauth::Request>(
+            &self,
+            method: &RequestMethod,
+            uri: &Uri,
+            request: &R,
+            options: &OAuthOptions<'_>,
+        ) -> String {
+            match *self {
+                Auth::OAuth1(ref token) => token.authorization(method, uri, request, options),
+                Auth::Bearer(ref token) => {
+                    BearerToken(token.as_ref()).authorization(method, uri, request, options)
+                }
+                Auth::Basic {
+                    ref username,
+                    ref password,
+                } => {
+                    let credentials = format!("{}:{}", username.as_ref(), password.as_ref());
+                    format!("Basic {}", base64::encode(credentials))
+                }
+            }
+        }
+
+        fn token_eq(&self, other: &Self) -> bool {
+            match (self, other) {
+                (Auth::OAuth1(a), Auth::OAuth1(b)) => a.token_eq(b),
+                (Auth::Bearer(a), Auth::Bearer(b)) => a.as_ref() == b.as_ref(),
+                (
+                    Auth::Basic {
+                        username: u1,
+                        password: p1,
+                    },
+                    Auth::Basic {
+                        username: u2,
+                        password: p2,
+                    },
+                ) => u1.as_ref() == u2.as_ref() && p1.as_ref() == p2.as_ref(),
+                _ => false,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Auth;
+
+    #[test]
+    fn v2_filtered_stream() {
+        let req = Builder::v2_filtered_stream("AAAA")
+            .tweet_fields("created_at,lang")
+            .backfill_minutes(5)
+            .build_request();
+
+        assert_eq!(req.method(), RequestMethod::GET);
+        assert_eq!(
+            req.uri(),
+            "https://api.twitter.com/2/tweets/search/stream?backfill_minutes=5&tweet.fields=created_at%2Clang",
+        );
+        assert_eq!(req.headers()[AUTHORIZATION], "Bearer AAAA");
+        assert!(req.body().is_empty());
+    }
+
+    #[test]
+    fn delimited() {
+        let token = Token::from_parts("ck", "cs", "ak", "as");
+        let req = Builder::new(token).delimited(true).build_request();
+        assert_eq!(
+            req.uri(),
+            "https://stream.twitter.com/1.1/statuses/sample.json?delimited=length"
+        );
+    }
+
+    #[test]
+    fn auth() {
+        let token = Token::from_parts("ck", "cs", "ak", "as");
+        let req = Builder::new(Auth::OAuth1(token)).into_request();
+        let authorization = req.headers()[AUTHORIZATION].to_str().unwrap();
+        assert!(authorization.starts_with("OAuth "));
+
+        let req = Builder::new(Auth::Bearer("AAAA"))
+            .track("@Twitter")
+            .build_request();
+        assert_eq!(req.method(), RequestMethod::POST);
+        assert_eq!(req.uri(), FILTER);
+        assert_eq!(req.headers()[AUTHORIZATION], "Bearer AAAA");
+        assert_eq!(req.body(), b"track=%40Twitter");
+    }
+
+    #[test]
+    fn param() {
+        let token = Token::from_parts("ck", "cs", "ak", "as");
+        let mut builder = Builder::new(token);
+        builder
+            .param("tweet_mode", "extended")
+            .and_then(|b| b.param("a", "1"))
+            .and_then(|b| b.param("z", "2"))
+            .and_then(|b| b.param("a", "@"))
+            .unwrap();
+        let req = builder.build_request();
+        assert_eq!(
+            req.uri(),
+            "https://stream.twitter.com/1.1/statuses/sample.json?a=%40&tweet_mode=extended&z=2",
+        );
+
+        let req = Builder::new(BearerToken("AAAA"))
+            .track("@Twitter")
+            .param("count", "10")
+            .unwrap()
+            .build_request();
+        assert_eq!(req.body(), b"count=10&track=%40Twitter");
+    }
+
+    #[test]
+    fn param_invalid() {
+        let mut builder = Builder::new(BearerToken("AAAA"));
+        for &key in &["", "a&b", "a b", "caf\u{e9}"] {
+            assert_eq!(
+                builder.param(key, "").err(),
+                Some(ParameterError::InvalidName(key.to_owned())),
+            );
+        }
+        assert!(builder.parameters.extra.is_empty());
+    }
+
+    #[test]
+    fn powertrack() {
+        let mut builder = Builder::new(BearerToken("AAAA"));
+        builder.powertrack("my-account", "prod").unwrap();
+        let expected = "https://gnip-stream.twitter.com/stream/powertrack/accounts/my-account/publishers/twitter/prod.json";
+        assert_eq!(builder.build_request().uri(), expected);
+
+        for &(account, label, invalid) in &[
+            ("", "prod", ""),
+            ("a/b", "prod", "a/b"),
+            ("my-account", "prod?x", "prod?x"),
+            ("my account", "prod", "my account"),
+        ] {
+            assert_eq!(
+                builder.powertrack(account, label).err(),
+                Some(ParameterError::InvalidPowerTrack(invalid.to_owned())),
+            );
+        }
+        assert_eq!(builder.build_request().uri(), expected);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn on_warning() {
+        use futures::executor::block_on;
+        use futures::{future, TryStreamExt};
+
+        let warnings = Arc::new(Mutex::new(Vec::new()));
+        let client = tower::service_fn(|req: http::Request<Vec<u8>>| {
+            assert_eq!(req.body(), b"stall_warnings=true&track=%40Twitter");
+            let body = concat!(
+                r#"{"warning":{"code":"FALLING_BEHIND","message":"","percent_full":60}}"#,
+                "\r\n{}\r\n",
+            );
+            let res = http::Response::new(hyper_pkg::Body::from(body));
+            future::ok::<_, hyper_pkg::Error>(res)
+        });
+
+        let stream = block_on(
+            Builder::new(BearerToken("AAAA"))
+                .track("@Twitter")
+                .on_warning({
+                    let warnings = warnings.clone();
+                    move |warning| warnings.lock().unwrap().push(warning)
+                })
+                .listen_with_client(client),
+        )
+        .unwrap();
+        let messages = block_on(stream.try_collect::<Vec<_>>()).unwrap();
+
+        assert_eq!(messages.len(), 2);
+        let warnings = warnings.lock().unwrap();
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].percent_full, Some(60));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn stall_warnings() {
+        use futures::executor::block_on;
+        use futures::{future, TryStreamExt};
+
+        use crate::error::Warning;
+
+        #[derive(Debug, serde::Deserialize)]
+        #[serde(untagged)]
+        enum Message {
+            Warning { warning: Warning },
+            Other(serde::de::IgnoredAny),
+        }
+
+        // A stall warning in the format sent by the Streaming API, with `delimited=length`.
+        const WARNING: &str = "{\"warning\":{\"code\":\"FALLING_BEHIND\",\"message\":\"Your connection is falling behind and messages are being queued for delivery to you. Your queue is now over 60% full. You will be disconnected when the queue is full.\",\"percent_full\": 60}}\r\n";
+        const TWEET: &str = "{\"id\":1,\"text\":\"warning\"}\r\n";
+
+        let client = tower::service_fn(|req: http::Request<Vec<u8>>| {
+            assert_eq!(
+                req.body(),
+                b"delimited=length&stall_warnings=true&track=%40Twitter",
+            );
+            let body = format!(
+                "\r\n{}\r\n{}{}\r\n{}\r\n",
+                TWEET.len(),
+                TWEET,
+                WARNING.len(),
+                WARNING,
+            );
+            let res = http::Response::new(hyper_pkg::Body::from(body));
+            future::ok::<_, hyper_pkg::Error>(res)
+        });
+
+        let stream = block_on(
+            Builder::new(BearerToken("AAAA"))
+                .track("@Twitter")
+                .delimited(true)
+                .stall_warnings(true)
+                .listen_with_client(client),
+        )
+        .unwrap();
+        let messages = block_on(stream.parse::<Message>().try_collect::<Vec<_>>()).unwrap();
+
+        assert_eq!(messages.len(), 2);
+        assert!(matches!(messages[0], Message::Other(_)));
+        match messages[1] {
+            Message::Warning { ref warning } => {
+                assert_eq!(warning.code, "FALLING_BEHIND");
+                assert!(warning
+                    .message
+                    .starts_with("Your connection is falling behind"));
+                assert_eq!(warning.percent_full, Some(60));
+            }
+            ref message => panic!("not a warning: {:?}", message),
+        }
+    }
+
+    #[test]
+    fn skip_invalid() {
+        use futures::executor::block_on;
+        use futures::{future, TryStreamExt};
+
+        let client = tower::service_fn(|_: http::Request<Vec<u8>>| {
+            let body: &[u8] = b"{\"id\":1}\r\n\xFF\r\n{\"id\":2}\r\n";
+            let res = http::Response::new(hyper_pkg::Body::from(body));
+            future::ok::<_, hyper_pkg::Error>(res)
+        });
+
+        let stream = block_on(
+            Builder::new(BearerToken("AAAA"))
+                .skip_invalid(true)
+                .listen_with_client(client),
+        )
+        .unwrap();
+        let messages = block_on(stream.map_ok(|s| s.to_string()).try_collect::<Vec<_>>());
+        assert_eq!(messages.unwrap(), ["{\"id\":1}", "{\"id\":2}"]);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn skip_invalid_json() {
+        use futures::executor::block_on;
+        use futures::{future, TryStreamExt};
+
+        #[derive(serde::Deserialize)]
+        struct Tweet {
+            id: u64,
+        }
+
+        let client = tower::service_fn(|_: http::Request<Vec<u8>>| {
+            let body = "{\"id\":1}\r\n{\"id\":\r\n{\"id\":2}\r\n";
+            let res = http::Response::new(hyper_pkg::Body::from(body));
+            future::ok::<_, hyper_pkg::Error>(res)
+        });
+
+        let stream = block_on(
+            Builder::new(BearerToken("AAAA"))
+                .skip_invalid(true)
+                .listen_with_client(client),
+        )
+        .unwrap();
+        let ids = stream.parse::<Tweet>().map_ok(|tweet| tweet.id);
+        assert_eq!(block_on(ids.try_collect::<Vec<_>>()).unwrap(), [1, 2]);
+    }
+
+    #[test]
+    fn rate_limited() {
+        use std::cell::Cell;
+
+        use futures::executor::block_on;
+        use futures::future;
+
+        let statuses = Cell::new(&[420, 429, 200, 420, 420][..]);
+        let mut client = tower::service_fn(|_: http::Request<Vec<u8>>| {
+            let (&status, rest) = statuses.get().split_first().unwrap();
+            statuses.set(rest);
+            let res = http::Response::builder()
+                .status(status)
+                .body(hyper_pkg::Body::from(format!("status {}", status)))
+                .unwrap();
+            future::ok::<_, hyper_pkg::Error>(res)
+        });
+
+        let counter = RateLimitCounter::new();
+        let mut builder = Builder::new(BearerToken("AAAA"));
+        builder.rate_limit_counter(counter.clone());
+        let mut backoffs = Vec::new();
+        for _ in 0..4 {
+            match block_on(builder.clone().listen_with_client(&mut client)) {
+                Err(crate::Error::RateLimited { backoff, body, .. }) => {
+                    assert!(body.starts_with(b"status 42"));
+                    backoffs.push(backoff.as_secs());
+                }
+                Ok(_) => backoffs.push(0),
+                Err(e) => panic!("{:?}", e),
+            }
+        }
+        assert_eq!(backoffs, [60, 120, 0, 60]);
+        assert_eq!(counter.attempts(), 1);
+
+        // A `Builder` without the counter does not double the back-off, even if it is cloned
+        // from a `Builder` that has been rate-limited.
+        builder.rate_limit_counter(None);
+        match block_on(builder.listen_with_client(&mut client)) {
+            Err(crate::Error::RateLimited { backoff, body, .. }) => {
+                assert_eq!(backoff.as_secs(), 60);
+                assert_eq!(body, "status 420");
+            }
+            _ => panic!(),
+        }
+        assert_eq!(counter.attempts(), 1);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn connect_timeout() {
+        let client = tower::service_fn(|_: http::Request<Vec<u8>>| {
+            futures::future::pending::<Result<http::Response<hyper_pkg::Body>, hyper_pkg::Error>>()
+        });
+        let result = Builder::new(BearerToken("AAAA"))
+            .connect_timeout(Duration::from_millis(10))
+            .listen_with_client(client)
+            .await;
+        assert!(matches!(result, Err(crate::Error::TimedOut)));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn spawn() {
+        use std::sync::{Arc, Mutex};
+
+        let client = tower::service_fn(|_: http::Request<Vec<u8>>| {
+            let body = hyper_pkg::Body::from("{\"id\":1}\r\n\r\n{\"id\":2}\r\n");
+            futures::future::ok::<_, hyper_pkg::Error>(http::Response::new(body))
+        });
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        let (task, _) = Builder::new(BearerToken("AAAA")).spawn(client, {
+            let messages = messages.clone();
+            move |message| messages.lock().unwrap().push(message.to_string())
+        });
+
+        task.await.unwrap().unwrap();
+        assert_eq!(*messages.lock().unwrap(), ["{\"id\":1}", "{\"id\":2}"]);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn spawn_abort() {
+        use std::sync::{Arc, Mutex};
+
+        // The body never ends since `tx` is kept alive.
+        let (mut tx, body) = hyper_pkg::Body::channel();
+        tx.try_send_data(Bytes::from_static(b"{\"id\":1}\r\n"))
+            .unwrap();
+        let body = Mutex::new(Some(body));
+        let client = tower::service_fn(move |_: http::Request<Vec<u8>>| {
+            let body = body.lock().unwrap().take().unwrap();
+            futures::future::ok::<_, hyper_pkg::Error>(http::Response::new(body))
+        });
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        let (task, abort) = Builder::new(BearerToken("AAAA")).spawn(client, {
+            let messages = messages.clone();
+            move |message| messages.lock().unwrap().push(message.to_string())
+        });
+
+        while messages.lock().unwrap().is_empty() {
+            tokio::task::yield_now().await;
+        }
+        abort.abort();
+        task.await.unwrap().unwrap();
+        assert_eq!(*messages.lock().unwrap(), ["{\"id\":1}"]);
+        // The connection is closed.
+        assert!(tx.send_data(Bytes::new()).await.is_err());
+    }
+
+    #[test]
+    fn user_agent() {
+        let req = Builder::new(BearerToken("AAAA")).build_request();
+        let user_agent = req.headers()[USER_AGENT].to_str().unwrap();
+        assert!(user_agent.starts_with("twitter-stream-rs/0."));
+    }
+
+    #[test]
+    fn track_phrases() {
+        let req = Builder::new(BearerToken("AAAA"))
+            .track_phrases(&["@Twitter", "Rust lang"])
+            .unwrap()
+            .build_request();
+        assert_eq!(req.body(), b"track=%40Twitter%2CRust%20lang");
+
+        let req = Builder::new(BearerToken("AAAA"))
+            .track("@Twitter")
+            .track_phrases(&[])
+            .unwrap()
+            .build_request();
+        assert_eq!(req.uri(), SAMPLE);
+    }
+
+    #[test]
+    fn from_uri() {
+        let token = Token::from_parts("ck", "cs", "ak", "as");
+        let uri = Uri::from_static(
+            "https://stream.twitter.com/1.1/statuses/sample.json\
+             ?language=en&follow=1,2&delimited=length&count=abc&tweet_mode=extended&q=a+b%2Bc",
+        );
+        let mut from_uri = Builder::from_uri(RequestMethod::GET, uri, token.as_ref()).unwrap();
+        let mut expected = Builder::new(token.as_ref());
+        expected
+            .endpoint((RequestMethod::GET, Uri::from_static(SAMPLE)))
+            .language("en")
+            .follow(&[1, 2][..])
+            .delimited(true)
+            .param("count", "abc")
+            .and_then(|b| b.param("tweet_mode", "extended"))
+            .and_then(|b| b.param("q", "a b+c"))
+            .unwrap();
+        for builder in [&mut from_uri, &mut expected].iter_mut() {
+            builder.oauth_nonce("nonce").oauth_timestamp(1);
+        }
+
+        let (from_uri, expected) = (from_uri.build_request(), expected.build_request());
+        assert_eq!(from_uri.uri(), expected.uri());
+        assert_eq!(
+            from_uri.headers()[AUTHORIZATION],
+            expected.headers()[AUTHORIZATION],
+        );
+        assert!(from_uri.uri().query().unwrap().contains("delimited=length"));
+    }
+
+    #[test]
+    fn from_uri_invalid() {
+        for &(query, name) in &[("track=a&a%26b=1", "a&b"), ("=1", ""), ("a+b", "a b")] {
+            let uri = format!("{}?{}", SAMPLE, query).parse().unwrap();
+            let result = Builder::from_uri(RequestMethod::GET, uri, BearerToken("AAAA"));
+            assert_eq!(
+                result.err(),
+                Some(ParameterError::InvalidName(name.to_owned())),
+            );
+        }
+    }
+
+    #[test]
+    fn eq() {
+        let token = Token::from_parts("ck", "cs", "ak", "as");
+        let sf = [BoundingBox::new(-122.75, 36.8, -121.75, 37.8)];
+        let mut builder = Builder::new(token);
+        builder.track("@Twitter").locations(&sf[..]);
+        let mut other = Builder::new(token);
+        other.locations(sf.to_vec()).track(String::from("@Twitter"));
+        assert_eq!(builder, other);
+        assert_eq!(builder, builder.clone());
+
+        other.param("replies", "all").unwrap();
+        assert_ne!(builder, other);
+        assert_ne!(
+            builder,
+            Builder::new(Token::from_parts("ck", "cs", "ak", "other")),
+        );
+        assert_ne!(
+            Builder::new(Auth::Bearer("a")),
+            Builder::new(Auth::Bearer("b"))
+        );
+
+        let nan = [BoundingBox {
+            west_longitude: f64::NAN,
+            ..sf[0]
+        }];
+        builder.locations(&nan[..]);
+        assert_eq!(builder, builder.clone());
+        let zero = [BoundingBox::new(0.0, 0.0, 1.0, 1.0)];
+        let negative_zero = [BoundingBox::new(-0.0, 0.0, 1.0, 1.0)];
+        assert_ne!(
+            builder.clone().with_locations(&zero[..]),
+            builder.clone().with_locations(&negative_zero[..]),
+        );
+    }
+
+    #[test]
+    fn dry_run() {
+        let dry_run = Builder::new(BearerToken("AAAA")).language("en").dry_run();
+        assert_eq!(dry_run.method, "GET");
+        assert_eq!(dry_run.uri, format!("{}?language=en", SAMPLE));
+        assert_eq!(dry_run.body, "");
+        assert_eq!(
+            dry_run.to_string(),
+            format!(
+                "curl -X GET '{}?language=en' -H 'Authorization: Bearer AAAA'",
+                SAMPLE,
+            ),
+        );
+
+        let dry_run = Builder::new(BearerToken("'")).track("a").dry_run();
+        assert_eq!(
+            dry_run.to_string(),
+            format!(
+                r"curl -X POST '{}' -H 'Authorization: Bearer '\''' -d 'track=a'",
+                FILTER,
+            ),
+        );
+    }
+
+    #[test]
+    fn replies() {
+        let req = Builder::new(BearerToken("AAAA"))
+            .track("@Twitter")
+            .replies(true)
+            .build_request();
+        assert_eq!(req.body(), b"replies=all&track=%40Twitter");
+    }
+
+    #[test]
+    fn stream_scope() {
+        let mut builder = Builder::new(BearerToken("AAAA"));
+        builder
+            .track("@Twitter")
+            .stream_scope(StreamScope::Followings);
+        assert_eq!(
+            builder.build_request().body(),
+            b"track=%40Twitter&with=followings",
+        );
+
+        builder.stream_scope(StreamScope::Custom("all".into()));
+        assert_eq!(builder.build_request().body(), b"track=%40Twitter&with=all");
+
+        builder.stream_scope(None);
+        assert_eq!(builder.build_request().body(), b"track=%40Twitter");
+
+        let req = Builder::new(BearerToken("AAAA"))
+            .with_stream_scope(StreamScope::User)
+            .build_request();
+        assert_eq!(req.uri().query(), Some("with=user"));
+    }
+
+    #[test]
+    fn consuming_setters() {
+        let token = Token::from_parts("ck", "cs", "ak", "as");
+        let counter = RateLimitCounter::new();
+        let mut expected = Builder::new(token.as_ref());
+        expected
+            .endpoint((RequestMethod::POST, Uri::from_static(FILTER)))
+            .user_agent(HeaderValue::from_static("test"))
+            .oauth_nonce("nonce")
+            .oauth_timestamp(1)
+            .delimited(true)
+            .stall_warnings(true)
+            .filter_level(FilterLevel::Low)
+            .language_enum(&[Language::English])
+            .follow(&[1][..])
+            .track("@Twitter")
+            .locations_tuples(&[(-122.75, 36.8, -121.75, 37.8)])
+            .count(10)
+            .replies(true)
+            .stream_scope(StreamScope::User)
+            .line_buffer_capacity(1024)
+            .max_line_length(4096)
+            .skip_invalid(true)
+            .rate_limit_counter(counter.clone())
+            .tweet_fields("lang")
+            .expansions("author_id")
+            .backfill_minutes(5);
+
+        let builder = Builder::new(token.as_ref())
+            .with_endpoint((RequestMethod::POST, Uri::from_static(FILTER)))
+            .with_user_agent(HeaderValue::from_static("test"))
+            .with_oauth_nonce("nonce")
+            .with_oauth_timestamp(1)
+            .with_delimited(true)
+            .with_stall_warnings(true)
+            .with_filter_level(FilterLevel::Low)
+            .with_language_enum(&[Language::English])
+            .with_follow(&[1][..])
+            .with_track("@Twitter")
+            .with_locations_tuples(&[(-122.75, 36.8, -121.75, 37.8)])
+            .with_count(10)
+            .with_replies(true)
+            .with_stream_scope(StreamScope::User)
+            .with_line_buffer_capacity(1024)
+            .with_max_line_length(4096)
+            .with_skip_invalid(true)
+            .with_rate_limit_counter(counter)
+            .with_tweet_fields("lang")
+            .with_expansions("author_id")
+            .with_backfill_minutes(5);
+
+        assert_eq!(builder, expected);
+        assert_eq!(builder.get_filter_level(), Some(&FilterLevel::Low));
+        assert_eq!(Builder::new(token.as_ref()).get_filter_level(), None);
+        assert_eq!(
+            builder.build_request().headers()[AUTHORIZATION],
+            expected.build_request().headers()[AUTHORIZATION],
+        );
+    }
+
+    #[test]
+    fn track_phrases_comma() {
+        let mut builder = Builder::new(BearerToken("AAAA"));
+        builder.track("@Twitter");
+        assert_eq!(
+            builder.track_phrases(&["foo", "foo,bar"]).err(),
+            Some(ParameterError::InvalidPhrase("foo,bar".to_owned())),
+        );
+        assert_eq!(
+            builder.track_phrases(&["", "foo"]).err(),
+            Some(ParameterError::InvalidPhrase(String::new())),
+        );
+        assert_eq!(
+            builder.track_phrases(&["foo", " "]).err(),
+            Some(ParameterError::InvalidPhrase(" ".to_owned())),
+        );
+        assert_eq!(builder.parameters.track, "@Twitter");
+    }
+
+    #[test]
+    fn validate() {
+        let follow = vec![1; ParameterError::MAX_FOLLOW + 1];
+        let mut builder = Builder::new(BearerToken("AAAA"));
+        builder.follow(&*follow);
+        assert_eq!(
+            builder.parameters.validate(),
+            Err(ParameterError::TooManyFollow(5001)),
+        );
+
+        let track = vec!["a"; ParameterError::MAX_TRACK];
+        builder.follow(&[][..]).track_phrases(&track).unwrap();
+        assert_eq!(builder.parameters.validate(), Ok(()));
+        builder.track(format!("{},b", track.join(",")));
+        assert_eq!(
+            builder.parameters.validate(),
+            Err(ParameterError::TooManyTrack(401)),
+        );
+    }
+
+    #[test]
+    fn languages() {
+        let mut builder = Builder::new(BearerToken("AAAA"));
+        builder.languages(&["en", "", "zh-tw"]).unwrap();
+        assert_eq!(builder.parameters.language, "en,zh-tw");
+        builder.languages(&[""]).unwrap();
+        assert_eq!(builder.parameters.language, "");
+    }
+
+    #[test]
+    fn languages_invalid() {
+        let mut builder = Builder::new(BearerToken("AAAA"));
+        builder.language("en");
+        for &lang in &["en,ja", "en ja", "en_US"] {
+            assert_eq!(
+                builder.languages(&["fr", lang]).err(),
+                Some(ParameterError::InvalidLanguage(lang.to_owned())),
+            );
+        }
+        assert_eq!(builder.parameters.language, "en");
+    }
+
+    #[test]
+    fn parse_filter_level() {
+        for level in &[FilterLevel::None, FilterLevel::Low, FilterLevel::Medium] {
+            assert_eq!(level.to_string().parse::<FilterLevel>().unwrap(), *level);
+        }
+        assert!("high".parse::<FilterLevel>().is_err());
+    }
+}
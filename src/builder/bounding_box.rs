@@ -1,6 +1,5 @@
-pub use http::Method as RequestMethod;
-pub use http::Uri;
-
+use std::error;
+use std::fmt::{self, Display, Formatter};
 use std::mem;
 use std::slice;
 
@@ -21,6 +20,11 @@ pub struct BoundingBox {
     pub north_latitude: f64,
 }
 
+/// An error returned by [`BoundingBox::try_new`] when the coordinates are out of range or
+/// in a wrong order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidBoundingBox(&'static str);
+
 impl BoundingBox {
     /// Creates a `BoundingBox` with the longitudes and latitudes of its sides.
     ///
@@ -47,6 +51,185 @@ impl BoundingBox {
         }
     }
 
+    /// Creates a `BoundingBox` after checking that its coordinates are valid.
+    ///
+    /// The longitudes must be in `[-180, 180]` and the latitudes in `[-90, 90]`, and the west and
+    /// south sides must be less than the east and north sides respectively. This catches
+    /// swapped coordinates (e.g. latitude and longitude in the wrong order), with which the
+    /// Streaming API would silently deliver nothing.
+    ///
+    /// A bounding box crossing the antimeridian is rejected, so split it into two at the
+    /// antimeridian instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use twitter_stream::builder::BoundingBox;
+    ///
+    /// assert_eq!(
+    ///     BoundingBox::try_new(-122.75, 36.8, -121.75, 37.8),
+    ///     Ok(BoundingBox::new(-122.75, 36.8, -121.75, 37.8)),
+    /// );
+    /// // Latitude and longitude swapped.
+    /// assert!(BoundingBox::try_new(36.8, -122.75, 37.8, -121.75).is_err());
+    /// ```
+    pub fn try_new(
+        west_longitude: f64,
+        south_latitude: f64,
+        east_longitude: f64,
+        north_latitude: f64,
+    ) -> Result<Self, InvalidBoundingBox> {
+        fn in_range(value: f64, limit: f64) -> bool {
+            // This is `false` for NaN.
+            -limit <= value && value <= limit
+        }
+
+        if !in_range(west_longitude, 180.0) || !in_range(east_longitude, 180.0) {
+            return Err(InvalidBoundingBox("longitude out of range [-180, 180]"));
+        }
+        if !in_range(south_latitude, 90.0) || !in_range(north_latitude, 90.0) {
+            return Err(InvalidBoundingBox("latitude out of range [-90, 90]"));
+        }
+        if west_longitude >= east_longitude {
+            return Err(InvalidBoundingBox(
+                "west longitude not less than east longitude",
+            ));
+        }
+        if south_latitude >= north_latitude {
+            return Err(InvalidBoundingBox(
+                "south latitude not less than north latitude",
+            ));
+        }
+
+        Ok(BoundingBox::new(
+            west_longitude,
+            south_latitude,
+            east_longitude,
+            north_latitude,
+        ))
+    }
+
+    /// Creates a `BoundingBox` centered at `longitude` and `latitude`, extending
+    /// `half_width` degrees east and west and `half_height` degrees north and south.
+    ///
+    /// The latitudes are clamped to `[-90, 90]`, and a `half_width` of 180 degrees or more
+    /// covers all longitudes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `half_width` or `half_height` is negative or NaN, if the box would
+    /// cross the antimeridian, or if the resulting box is rejected by
+    /// [`try_new`](BoundingBox::try_new), e.g. because it has no area.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use twitter_stream::builder::BoundingBox;
+    ///
+    /// let bbox = BoundingBox::from_center(-122.25, 37.3, 0.5, 0.5);
+    /// assert_eq!(bbox, Ok(BoundingBox::new(-122.75, 36.8, -121.75, 37.8)));
+    ///
+    /// let bbox = BoundingBox::from_center(0.0, 89.0, 180.0, 2.0);
+    /// assert_eq!(bbox, Ok(BoundingBox::new(-180.0, 87.0, 180.0, 90.0)));
+    ///
+    /// // Crossing the antimeridian.
+    /// assert!(BoundingBox::from_center(179.0, 0.0, 2.0, 2.0).is_err());
+    /// ```
+    pub fn from_center(
+        longitude: f64,
+        latitude: f64,
+        half_width: f64,
+        half_height: f64,
+    ) -> Result<Self, InvalidBoundingBox> {
+        if [half_width, half_height]
+            .iter()
+            .any(|&half| half.is_nan() || half < 0.0)
+        {
+            return Err(InvalidBoundingBox("negative half width or half height"));
+        }
+
+        let (west_longitude, east_longitude) = if half_width >= 180.0 {
+            (-180.0, 180.0)
+        } else {
+            let (west, east) = (longitude - half_width, longitude + half_width);
+            if (-180.0..=180.0).contains(&longitude) && (west < -180.0 || east > 180.0) {
+                return Err(InvalidBoundingBox("bounding box crossing the antimeridian"));
+            }
+            (west, east)
+        };
+
+        BoundingBox::try_new(
+            west_longitude,
+            (latitude - half_height).clamp(-90.0, 90.0),
+            east_longitude,
+            (latitude + half_height).clamp(-90.0, 90.0),
+        )
+    }
+
+    /// Returns `true` if the point at `longitude` and `latitude` is inside the bounding box
+    /// (including its sides).
+    ///
+    /// A bounding box whose `west_longitude` is greater than its `east_longitude` is regarded as
+    /// crossing the antimeridian.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use twitter_stream::builder::BoundingBox;
+    ///
+    /// let san_francisco = BoundingBox::new(-122.75, 36.8, -121.75, 37.8);
+    /// assert!(san_francisco.contains(-122.42, 37.77));
+    /// assert!(!san_francisco.contains(-74.0, 40.71));
+    ///
+    /// let fiji = BoundingBox::new(177.0, -21.0, -178.0, -12.0);
+    /// assert!(fiji.contains(178.44, -18.14));
+    /// assert!(fiji.contains(-179.0, -16.0));
+    /// ```
+    pub fn contains(&self, longitude: f64, latitude: f64) -> bool {
+        self.south_latitude <= latitude
+            && latitude <= self.north_latitude
+            && self
+                .longitude_ranges()
+                .iter()
+                .any(|&(west, east)| west <= longitude && longitude <= east)
+    }
+
+    /// Returns `true` if the bounding box and `other` overlap (including touching sides).
+    ///
+    /// Bounding boxes crossing the antimeridian are handled in the same way as
+    /// [`contains`](BoundingBox::contains).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use twitter_stream::builder::BoundingBox;
+    ///
+    /// let a = BoundingBox::new(0.0, 0.0, 10.0, 10.0);
+    /// assert!(a.intersects(&BoundingBox::new(5.0, 5.0, 15.0, 15.0)));
+    /// assert!(!a.intersects(&BoundingBox::new(11.0, 0.0, 15.0, 10.0)));
+    /// ```
+    pub fn intersects(&self, other: &BoundingBox) -> bool {
+        self.south_latitude <= other.north_latitude
+            && other.south_latitude <= self.north_latitude
+            && self.longitude_ranges().iter().any(|&(west, east)| {
+                other
+                    .longitude_ranges()
+                    .iter()
+                    .any(|&(other_west, other_east)| west <= other_east && other_west <= east)
+            })
+    }
+
+    /// Returns the ranges of longitude covered by the bounding box, splitting the box at
+    /// the antimeridian if it crosses it.
+    fn longitude_ranges(&self) -> [(f64, f64); 2] {
+        if self.west_longitude <= self.east_longitude {
+            let range = (self.west_longitude, self.east_longitude);
+            [range, range]
+        } else {
+            [(self.west_longitude, 180.0), (-180.0, self.east_longitude)]
+        }
+    }
+
     /// Creates a slice of `BoundingBox`-es from a slice of arrays of
     /// `[west_longitude, south_latitude, east_longitude, north_latitude]`.
     ///
@@ -224,8 +407,120 @@ impl From<((f64, f64), (f64, f64))> for BoundingBox {
     }
 }
 
+impl Display for InvalidBoundingBox {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid bounding box: {}", self.0)
+    }
+}
+
+impl error::Error for InvalidBoundingBox {}
+
+/// Serializes a `BoundingBox` as an array of
+/// `[west_longitude, south_latitude, east_longitude, north_latitude]`.
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl serde::Serialize for BoundingBox {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        AsRef::<[f64; 4]>::as_ref(self).serialize(serializer)
+    }
+}
+
+/// Deserializes a `BoundingBox` from either an array of
+/// `[west_longitude, south_latitude, east_longitude, north_latitude]` or an array of two
+/// `[longitude, latitude]` pairs of the southwest and northeast edges.
+///
+/// # Example
+///
+/// ```
+/// use twitter_stream::builder::BoundingBox;
+///
+/// #[derive(serde::Deserialize)]
+/// struct Config {
+///     locations: Vec<BoundingBox>,
+/// }
+///
+/// let config = r#"{"locations": [[-122.75, 36.8, -121.75, 37.8], [[-74.0, 40.0], [-73.0, 41.0]]]}"#;
+/// let config: Config = serde_json::from_str(config).unwrap();
+///
+/// assert_eq!(config.locations, [
+///     BoundingBox::new(-122.75, 36.8, -121.75, 37.8),
+///     BoundingBox::new(-74.0, 40.0, -73.0, 41.0),
+/// ]);
+///
+/// let json = serde_json::to_string(&config.locations[1]).unwrap();
+/// assert_eq!(json, "[-74.0,40.0,-73.0,41.0]");
+/// ```
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+impl<'de> serde::Deserialize<'de> for BoundingBox {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Flat([f64; 4]),
+            Nested([[f64; 2]; 2]),
+        }
+
+        match Repr::deserialize(deserializer)? {
+            Repr::Flat(array) => Ok(array.into()),
+            Repr::Nested([[west, south], [east, north]]) => {
+                Ok(BoundingBox::new(west, south, east, north))
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::*;
+
+    #[test]
+    fn intersects_antimeridian() {
+        let fiji = BoundingBox::new(177.0, -21.0, -178.0, -12.0);
+        assert!(fiji.intersects(&BoundingBox::new(-179.0, -20.0, -175.0, -15.0)));
+        assert!(fiji.intersects(&BoundingBox::new(170.0, -20.0, 178.0, -15.0)));
+        assert!(fiji.intersects(&BoundingBox::new(179.0, -20.0, -179.0, -15.0)));
+        assert!(!fiji.intersects(&BoundingBox::new(-170.0, -20.0, 170.0, -15.0)));
+        assert!(!fiji.intersects(&BoundingBox::new(178.0, 0.0, 179.0, 10.0)));
+    }
+
+    #[test]
+    fn try_new() {
+        assert!(BoundingBox::try_new(-180.0, -90.0, 180.0, 90.0).is_ok());
+        assert!(BoundingBox::try_new(f64::NAN, 36.8, -121.75, 37.8).is_err());
+        assert!(BoundingBox::try_new(-122.75, 36.8, 181.0, 37.8).is_err());
+        assert!(BoundingBox::try_new(-122.75, -91.0, -121.75, 37.8).is_err());
+        assert!(BoundingBox::try_new(-121.75, 36.8, -122.75, 37.8).is_err());
+        assert!(BoundingBox::try_new(-122.75, 37.8, -121.75, 36.8).is_err());
+    }
+
+    #[test]
+    fn from_center() {
+        assert_eq!(
+            BoundingBox::from_center(179.0, 0.0, 1.0, 1.0),
+            Ok(BoundingBox::new(178.0, -1.0, 180.0, 1.0)),
+        );
+        assert_eq!(
+            BoundingBox::from_center(10.0, 0.0, 200.0, 100.0),
+            Ok(BoundingBox::new(-180.0, -90.0, 180.0, 90.0)),
+        );
+
+        // Crossing the antimeridian.
+        assert!(BoundingBox::from_center(179.0, 0.0, 2.0, 1.0).is_err());
+        assert!(BoundingBox::from_center(-179.5, 0.0, 1.0, 1.0).is_err());
+
+        // Negative or NaN half extents.
+        assert!(BoundingBox::from_center(0.0, 0.0, -1.0, 1.0).is_err());
+        assert!(BoundingBox::from_center(0.0, 0.0, 1.0, -1.0).is_err());
+        assert!(BoundingBox::from_center(0.0, 0.0, f64::NAN, 1.0).is_err());
+        assert!(BoundingBox::from_center(0.0, 0.0, 1.0, f64::NAN).is_err());
+
+        // No area or out of range.
+        assert!(BoundingBox::from_center(0.0, 0.0, 0.0, 1.0).is_err());
+        assert!(BoundingBox::from_center(0.0, 90.0, 1.0, 0.0).is_err());
+        assert!(BoundingBox::from_center(200.0, 0.0, 1.0, 1.0).is_err());
+    }
+
     mod soundness {
         use slice_of_array::SliceNestExt;
 
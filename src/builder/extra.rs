@@ -0,0 +1,83 @@
+use std::borrow::Cow;
+use std::collections::btree_map;
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::iter::Peekable;
+
+use oauth::serializer::Serializer;
+
+use super::Parameters;
+
+/// Extra parameters set with `Builder::param`, sorted by their keys.
+pub type Extra<'a> = BTreeMap<Cow<'a, str>, Cow<'a, str>>;
+
+/// `Parameters` merged with its extra parameters.
+pub struct WithExtra<'p, 'a>(pub &'p Parameters<'a>);
+
+/// A `Serializer` adapter inserting the extra parameters between the parameters serialized by
+/// the inner `Serializer`, keeping the parameters in byte ascending order.
+struct MergeExtra<'p, 'a, S> {
+    inner: S,
+    extra: Peekable<btree_map::Iter<'p, Cow<'a, str>, Cow<'a, str>>>,
+}
+
+impl oauth::Request for WithExtra<'_, '_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> S::Output {
+        oauth::Request::serialize(
+            self.0,
+            MergeExtra {
+                inner: serializer,
+                extra: self.0.extra.iter().peekable(),
+            },
+        )
+    }
+}
+
+impl<S: Serializer> MergeExtra<'_, '_, S> {
+    fn serialize_extra_before(&mut self, key: &str) {
+        while let Some((k, v)) = self.extra.next_if(|&(k, _)| **k < *key) {
+            self.inner.serialize_parameter(k, v);
+        }
+    }
+}
+
+macro_rules! serialize_oauth {
+    ($($method:ident => $key:expr,)*) => {$(
+        fn $method(&mut self) {
+            self.serialize_extra_before($key);
+            self.inner.$method();
+        }
+    )*};
+}
+
+impl<S: Serializer> Serializer for MergeExtra<'_, '_, S> {
+    type Output = S::Output;
+
+    fn serialize_parameter<V: Display>(&mut self, k: &str, v: V) {
+        self.serialize_extra_before(k);
+        self.inner.serialize_parameter(k, v);
+    }
+
+    fn serialize_parameter_encoded<V: Display>(&mut self, k: &str, v: V) {
+        self.serialize_extra_before(k);
+        self.inner.serialize_parameter_encoded(k, v);
+    }
+
+    serialize_oauth! {
+        serialize_oauth_callback => "oauth_callback",
+        serialize_oauth_consumer_key => "oauth_consumer_key",
+        serialize_oauth_nonce => "oauth_nonce",
+        serialize_oauth_signature_method => "oauth_signature_method",
+        serialize_oauth_timestamp => "oauth_timestamp",
+        serialize_oauth_token => "oauth_token",
+        serialize_oauth_verifier => "oauth_verifier",
+        serialize_oauth_version => "oauth_version",
+    }
+
+    fn end(mut self) -> S::Output {
+        for (k, v) in self.extra {
+            self.inner.serialize_parameter(k, v);
+        }
+        self.inner.end()
+    }
+}
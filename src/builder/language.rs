@@ -0,0 +1,239 @@
+use std::borrow::Cow;
+use std::convert::Infallible;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+macro_rules! languages {
+    ($($(#[$attr:meta])* $V:ident = $code:expr,)*) => {
+        /// A language identifier to be used with the [`language`] parameter.
+        ///
+        /// The variants cover the languages that Twitter detects on Tweets. Any other
+        /// BCP 47 language identifier can be specified with the `Custom` variant.
+        ///
+        /// Parsing a language identifier with [`FromStr`] never fails: an identifier not listed
+        /// below is parsed as `Custom`.
+        ///
+        /// [`language`]: https://developer.twitter.com/en/docs/tweets/filter-realtime/guides/basic-stream-parameters#language
+        #[derive(Clone, Debug, PartialEq, Hash, Eq)]
+        #[non_exhaustive]
+        pub enum Language<'a> {
+            $(
+                $(#[$attr])*
+                $V,
+            )*
+            /// A language identifier that is not listed above.
+            Custom(Cow<'a, str>),
+        }
+
+        impl AsRef<str> for Language<'_> {
+            fn as_ref(&self) -> &str {
+                match *self {
+                    $(Language::$V => $code,)*
+                    Language::Custom(ref code) => code,
+                }
+            }
+        }
+
+        impl FromStr for Language<'static> {
+            type Err = Infallible;
+
+            fn from_str(s: &str) -> Result<Self, Infallible> {
+                Ok(match s {
+                    $($code => Language::$V,)*
+                    _ => Language::Custom(s.to_owned().into()),
+                })
+            }
+        }
+
+        #[cfg(test)]
+        const ALL: &[Language<'static>] = &[$(Language::$V),*];
+    };
+}
+
+languages! {
+    /// Amharic (`"am"`)
+    Amharic = "am",
+    /// Arabic (`"ar"`)
+    Arabic = "ar",
+    /// Armenian (`"hy"`)
+    Armenian = "hy",
+    /// Basque (`"eu"`)
+    Basque = "eu",
+    /// Bengali (`"bn"`)
+    Bengali = "bn",
+    /// Bulgarian (`"bg"`)
+    Bulgarian = "bg",
+    /// Burmese (`"my"`)
+    Burmese = "my",
+    /// Catalan (`"ca"`)
+    Catalan = "ca",
+    /// Chinese (`"zh"`)
+    Chinese = "zh",
+    /// Czech (`"cs"`)
+    Czech = "cs",
+    /// Danish (`"da"`)
+    Danish = "da",
+    /// Dutch (`"nl"`)
+    Dutch = "nl",
+    /// English (`"en"`)
+    English = "en",
+    /// Estonian (`"et"`)
+    Estonian = "et",
+    /// Finnish (`"fi"`)
+    Finnish = "fi",
+    /// French (`"fr"`)
+    French = "fr",
+    /// Georgian (`"ka"`)
+    Georgian = "ka",
+    /// German (`"de"`)
+    German = "de",
+    /// Greek (`"el"`)
+    Greek = "el",
+    /// Gujarati (`"gu"`)
+    Gujarati = "gu",
+    /// Haitian Creole (`"ht"`)
+    HaitianCreole = "ht",
+    /// Hebrew (`"iw"`)
+    Hebrew = "iw",
+    /// Hindi (`"hi"`)
+    Hindi = "hi",
+    /// Hungarian (`"hu"`)
+    Hungarian = "hu",
+    /// Icelandic (`"is"`)
+    Icelandic = "is",
+    /// Indonesian (`"in"`)
+    Indonesian = "in",
+    /// Italian (`"it"`)
+    Italian = "it",
+    /// Japanese (`"ja"`)
+    Japanese = "ja",
+    /// Kannada (`"kn"`)
+    Kannada = "kn",
+    /// Khmer (`"km"`)
+    Khmer = "km",
+    /// Korean (`"ko"`)
+    Korean = "ko",
+    /// Lao (`"lo"`)
+    Lao = "lo",
+    /// Latvian (`"lv"`)
+    Latvian = "lv",
+    /// Lithuanian (`"lt"`)
+    Lithuanian = "lt",
+    /// Malayalam (`"ml"`)
+    Malayalam = "ml",
+    /// Maldivian (`"dv"`)
+    Maldivian = "dv",
+    /// Marathi (`"mr"`)
+    Marathi = "mr",
+    /// Nepali (`"ne"`)
+    Nepali = "ne",
+    /// Norwegian (`"no"`)
+    Norwegian = "no",
+    /// Oriya (`"or"`)
+    Oriya = "or",
+    /// Panjabi (`"pa"`)
+    Panjabi = "pa",
+    /// Pashto (`"ps"`)
+    Pashto = "ps",
+    /// Persian (`"fa"`)
+    Persian = "fa",
+    /// Polish (`"pl"`)
+    Polish = "pl",
+    /// Portuguese (`"pt"`)
+    Portuguese = "pt",
+    /// Romanian (`"ro"`)
+    Romanian = "ro",
+    /// Russian (`"ru"`)
+    Russian = "ru",
+    /// Serbian (`"sr"`)
+    Serbian = "sr",
+    /// Sindhi (`"sd"`)
+    Sindhi = "sd",
+    /// Sinhala (`"si"`)
+    Sinhala = "si",
+    /// Slovak (`"sk"`)
+    Slovak = "sk",
+    /// Slovenian (`"sl"`)
+    Slovenian = "sl",
+    /// Sorani Kurdish (`"ckb"`)
+    SoraniKurdish = "ckb",
+    /// Spanish (`"es"`)
+    Spanish = "es",
+    /// Swedish (`"sv"`)
+    Swedish = "sv",
+    /// Tagalog (`"tl"`)
+    Tagalog = "tl",
+    /// Tamil (`"ta"`)
+    Tamil = "ta",
+    /// Telugu (`"te"`)
+    Telugu = "te",
+    /// Thai (`"th"`)
+    Thai = "th",
+    /// Tibetan (`"bo"`)
+    Tibetan = "bo",
+    /// Turkish (`"tr"`)
+    Turkish = "tr",
+    /// Ukrainian (`"uk"`)
+    Ukrainian = "uk",
+    /// Urdu (`"ur"`)
+    Urdu = "ur",
+    /// Uyghur (`"ug"`)
+    Uyghur = "ug",
+    /// Vietnamese (`"vi"`)
+    Vietnamese = "vi",
+    /// Welsh (`"cy"`)
+    Welsh = "cy",
+}
+
+impl Display for Language<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        AsRef::<str>::as_ref(self).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn codes() {
+        let mut codes = HashSet::new();
+        for lang in ALL {
+            let code = lang.as_ref();
+            assert!(codes.insert(code), "duplicate code: {:?}", code);
+            assert!(
+                code.bytes().all(|b| b.is_ascii_lowercase()),
+                "invalid code: {:?}",
+                code,
+            );
+            assert_eq!(lang.to_string(), code);
+        }
+    }
+
+    #[test]
+    fn custom() {
+        let lang = Language::Custom("zh-tw".into());
+        assert_eq!(lang.as_ref(), "zh-tw");
+        assert_eq!(lang.to_string(), "zh-tw");
+        assert_eq!(format!("{:>6}", Language::English), "    en");
+    }
+
+    #[test]
+    fn round_trip() {
+        for lang in ALL {
+            assert_eq!(lang.to_string().parse::<Language<'_>>().unwrap(), *lang);
+        }
+
+        let lang = Language::Custom("zh-tw".into());
+        assert_eq!(lang.to_string().parse::<Language<'_>>().unwrap(), lang);
+        // A listed code is parsed as the corresponding variant rather than `Custom`.
+        assert_eq!(
+            Language::Custom("en".into())
+                .to_string()
+                .parse::<Language<'_>>(),
+            Ok(Language::English),
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use http::header::AUTHORIZATION;
+use http::{Request, Uri};
+use http_body::Body;
+
+use super::{Authorize, OAuthOptions, RequestMethod};
+use crate::service::HttpService;
+use crate::Error;
+
+const USERS_LOOKUP: &str = "https://api.twitter.com/1.1/users/lookup.json";
+
+/// The maximum number of screen names in a `users/lookup` request.
+const MAX_SCREEN_NAMES: usize = 100;
+
+#[derive(oauth::Request)]
+struct UsersLookup<'a> {
+    screen_name: &'a str,
+}
+
+/// Resolves `screen_names` to user IDs with the `users/lookup` endpoint.
+pub async fn user_ids<S, B, T>(
+    client: &mut S,
+    token: &T,
+    screen_names: &[&str],
+) -> Result<Vec<u64>, Error<S::Error>>
+where
+    S: HttpService<B>,
+    S::ResponseBody: Body<Error = S::Error>,
+    B: From<Vec<u8>>,
+    T: Authorize,
+{
+    #[derive(serde::Deserialize)]
+    struct User {
+        id: u64,
+    }
+
+    let mut ids = Vec::with_capacity(screen_names.len());
+
+    for chunk in screen_names.chunks(MAX_SCREEN_NAMES) {
+        let screen_name = chunk
+            .iter()
+            .map(|name| name.trim_start_matches('@'))
+            .collect::<Vec<_>>()
+            .join(",");
+        let parameters = UsersLookup {
+            screen_name: &screen_name,
+        };
+        let endpoint = Uri::from_static(USERS_LOOKUP);
+        let authorization = token.authorization(
+            &RequestMethod::GET,
+            &endpoint,
+            &parameters,
+            &OAuthOptions::default(),
+        );
+        let uri = oauth::to_uri_query(endpoint.to_string(), &parameters);
+        let req = Request::get(uri)
+            .header(AUTHORIZATION, authorization)
+            .body(Vec::new())
+            .unwrap();
+
+        let users: Vec<User> = crate::rest::send(client, req).await?;
+        ids.extend(users.into_iter().map(|user| user.id));
+    }
+
+    Ok(ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+    use futures::future;
+    use tower::service_fn;
+
+    use super::*;
+    use crate::BearerToken;
+
+    #[test]
+    fn user_ids() {
+        let mut client = service_fn(|req: Request<Vec<u8>>| {
+            assert_eq!(
+                req.uri(),
+                "https://api.twitter.com/1.1/users/lookup.json?screen_name=Twitter%2CTwitterDev",
+            );
+            assert_eq!(req.headers()[AUTHORIZATION], "Bearer AAAA");
+            let body = hyper_pkg::Body::from(r#"[{"id":783214},{"id":2244994945}]"#);
+            future::ok::<_, hyper_pkg::Error>(http::Response::new(body))
+        });
+
+        let ids = block_on(super::user_ids(
+            &mut client,
+            &BearerToken("AAAA"),
+            &["@Twitter", "TwitterDev"],
+        ))
+        .unwrap();
+        assert_eq!(ids, [783214, 2244994945]);
+    }
+}
@@ -0,0 +1,155 @@
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use futures_core::{ready, Stream};
+use http_body::Body;
+
+use crate::{Error, TwitterStream};
+
+/// A future returned by [`TwitterStream::close`], resolving to the messages drained from
+/// the stream after closing the connection.
+#[must_use = "futures do nothing unless you `.await` or poll them"]
+pub struct Close<B> {
+    // `None` once the connection has been closed.
+    stream: Option<Pin<Box<TwitterStream<B>>>>,
+    messages: Vec<string::String<Bytes>>,
+    max_messages: usize,
+    // Whether the messages available without waiting for the network have been read.
+    drained: bool,
+}
+
+impl<B> Close<B> {
+    pub(crate) fn new(stream: TwitterStream<B>, max_messages: usize) -> Self {
+        Close {
+            stream: Some(Box::pin(stream)),
+            messages: Vec::new(),
+            max_messages,
+            drained: false,
+        }
+    }
+
+    fn finish<E>(&mut self, result: Result<(), E>) -> (Vec<string::String<Bytes>>, Result<(), E>) {
+        // Dropping the body closes the connection.
+        self.stream = None;
+        (mem::take(&mut self.messages), result)
+    }
+}
+
+impl<B: Body> Future for Close<B> {
+    type Output = (Vec<string::String<Bytes>>, Result<(), Error<B::Error>>);
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let stream = this
+            .stream
+            .as_mut()
+            .expect("`Close` polled after completion");
+
+        if !this.drained {
+            while this.messages.len() < this.max_messages {
+                match stream.as_mut().poll_next(cx) {
+                    Poll::Ready(Some(Ok(message))) => this.messages.push(message),
+                    Poll::Ready(Some(Err(e))) => return Poll::Ready(this.finish(Err(e))),
+                    Poll::Ready(None) | Poll::Pending => break,
+                }
+            }
+            this.drained = true;
+        }
+
+        let result = ready!(stream.as_mut().poll_flush_tee(cx));
+        Poll::Ready(this.finish(result))
+    }
+}
+
+// The stream is boxed and the other fields are never pinned.
+impl<B> Unpin for Close<B> {}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+
+    fn strs(messages: &[string::String<Bytes>]) -> Vec<&str> {
+        messages.iter().map(|s| &**s).collect()
+    }
+
+    #[test]
+    fn drain() {
+        // Keep `tx` alive so that the body waits for more data after the chunk.
+        let (mut tx, body) = hyper_pkg::Body::channel();
+        let chunk = Bytes::from_static(b"{\"id\":1}\r\n\r\n{\"id\":2}\r\n{\"id\"");
+        tx.try_send_data(chunk).unwrap();
+        let (messages, result) = block_on(TwitterStream::new(body).close(usize::MAX));
+        assert_eq!(strs(&messages), ["{\"id\":1}", "{\"id\":2}"]);
+        assert!(result.is_ok());
+
+        let (mut tx, body) = hyper_pkg::Body::channel();
+        let chunk = Bytes::from_static(b"{\"id\":1}\r\n{\"id\":2}\r\n{\"id\":3}\r\n");
+        tx.try_send_data(chunk).unwrap();
+        let (messages, result) = block_on(TwitterStream::new(body).close(2));
+        assert_eq!(strs(&messages), ["{\"id\":1}", "{\"id\":2}"]);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn error() {
+        let body = hyper_pkg::Body::from(&b"{\"id\":1}\r\n\xff\r\n{\"id\":2}\r\n"[..]);
+        let (messages, result) = block_on(TwitterStream::new(body).close(usize::MAX));
+        assert_eq!(strs(&messages), ["{\"id\":1}"]);
+        assert!(matches!(result, Err(Error::Utf8(_))));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn flush_tee() {
+        use std::io;
+        use std::sync::{Arc, Mutex};
+
+        // A writer which is not ready on the first few writes.
+        #[derive(Clone, Default)]
+        struct Writer {
+            data: Arc<Mutex<Vec<u8>>>,
+            pending: Arc<Mutex<u32>>,
+        }
+
+        impl tokio::io::AsyncWrite for Writer {
+            fn poll_write(
+                self: Pin<&mut Self>,
+                cx: &mut Context<'_>,
+                buf: &[u8],
+            ) -> Poll<io::Result<usize>> {
+                let mut pending = self.pending.lock().unwrap();
+                if *pending > 0 {
+                    *pending -= 1;
+                    cx.waker().wake_by_ref();
+                    return Poll::Pending;
+                }
+                self.data.lock().unwrap().extend_from_slice(buf);
+                Poll::Ready(Ok(buf.len()))
+            }
+
+            fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        let (mut tx, body) = hyper_pkg::Body::channel();
+        tx.try_send_data(Bytes::from_static(b"{\"id\":1}\r\n"))
+            .unwrap();
+        let writer = Writer::default();
+        *writer.pending.lock().unwrap() = 3;
+        let stream = TwitterStream::new(body).tee(writer.clone());
+        let (messages, result) = block_on(stream.close(usize::MAX));
+        assert_eq!(strs(&messages), ["{\"id\":1}"]);
+        assert!(result.is_ok());
+        assert_eq!(*writer.data.lock().unwrap(), b"{\"id\":1}\r\n");
+    }
+}
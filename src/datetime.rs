@@ -0,0 +1,59 @@
+use chrono::format::{self, Fixed, Item, Numeric, Pad, ParseError, Parsed};
+use chrono::{DateTime, Utc};
+
+/// The format of the timestamps in the Twitter API v1.1, i.e. `"%a %b %e %H:%M:%S %z %Y"`.
+const ITEMS: &[Item<'static>] = &[
+    Item::Fixed(Fixed::ShortWeekdayName),
+    Item::Space(" "),
+    Item::Fixed(Fixed::ShortMonthName),
+    Item::Space(" "),
+    Item::Numeric(Numeric::Day, Pad::Space),
+    Item::Space(" "),
+    Item::Numeric(Numeric::Hour, Pad::Zero),
+    Item::Literal(":"),
+    Item::Numeric(Numeric::Minute, Pad::Zero),
+    Item::Literal(":"),
+    Item::Numeric(Numeric::Second, Pad::Zero),
+    Item::Space(" "),
+    Item::Fixed(Fixed::TimezoneOffset),
+    Item::Space(" "),
+    Item::Numeric(Numeric::Year, Pad::Zero),
+];
+
+/// Parses a timestamp in the format of the `created_at` field of the Twitter API v1.1 objects,
+/// e.g. `"Wed Oct 10 20:19:24 +0000 2018"`.
+///
+/// # Example
+///
+/// ```
+/// use chrono::{TimeZone, Utc};
+///
+/// let created_at = twitter_stream::parse_twitter_time("Wed Oct 10 20:19:24 +0000 2018").unwrap();
+/// assert_eq!(created_at, Utc.with_ymd_and_hms(2018, 10, 10, 20, 19, 24).unwrap());
+/// ```
+pub fn parse_twitter_time(s: &str) -> Result<DateTime<Utc>, ParseError> {
+    let mut parsed = Parsed::new();
+    format::parse(&mut parsed, s, ITEMS.iter())?;
+    parsed.to_datetime().map(|dt| dt.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            parse_twitter_time("Sat Jan  1 09:05:00 +0900 2022").unwrap(),
+            Utc.with_ymd_and_hms(2022, 1, 1, 0, 5, 0).unwrap(),
+        );
+        assert_eq!(
+            parse_twitter_time("Sat Jan 1 09:05:00 +0900 2022").unwrap(),
+            DateTime::parse_from_str("Sat Jan 1 09:05:00 +0900 2022", "%a %b %e %H:%M:%S %z %Y")
+                .unwrap(),
+        );
+        assert!(parse_twitter_time("2022-01-01T00:05:00Z").is_err());
+    }
+}
@@ -5,16 +5,375 @@ pub use http::StatusCode;
 use std::error;
 use std::fmt::{self, Display, Formatter};
 use std::str::Utf8Error;
+use std::time::Duration;
+
+use bytes::Bytes;
+use http::header::{HeaderMap, RETRY_AFTER};
 
 /// An error occurred while trying to connect to a Stream.
+///
+/// Some of the variants are only available with certain features, so this enum is
+/// `#[non_exhaustive]` to keep the features additive.
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error<E = Box<dyn error::Error + Send + Sync>> {
-    /// An HTTP error from the Stream.
+    /// The server responded with a status code other than `200 OK`, an empty body and no
+    /// `Retry-After` header.
+    ///
+    /// When connecting to a Stream, `420 Enhance Your Calm` and `429 Too Many Requests` are
+    /// reported as [`RateLimited`](Error::RateLimited) instead.
     Http(StatusCode),
+    /// The Streaming API refused the connection with the status code `420 Enhance Your Calm` or
+    /// `429 Too Many Requests` because of too frequent connection attempts.
+    ///
+    /// `backoff` is the duration to wait before reconnecting, which is 1 minute unless
+    /// the attempts are counted with a [`RateLimitCounter`]. Reconnecting earlier may get your
+    /// IP address banned temporarily.
+    ///
+    /// [`RateLimitCounter`]: crate::builder::RateLimitCounter
+    RateLimited {
+        /// The duration to wait before reconnecting.
+        backoff: Duration,
+        /// The value of the `Retry-After` header in seconds, if any.
+        retry_after: Option<Duration>,
+        /// The response body, which may describe the error. It is truncated to 64 KiB.
+        body: Bytes,
+    },
+    /// The server responded with a status code other than `200 OK` and a non-empty body or
+    /// a `Retry-After` header.
+    ///
+    /// If the `json` feature is enabled and the body is a list of Twitter API errors,
+    /// [`Twitter`](Error::Twitter) is returned instead. When connecting to a Stream,
+    /// `420 Enhance Your Calm` and `429 Too Many Requests` are reported as
+    /// [`RateLimited`](Error::RateLimited) regardless of the body.
+    #[cfg_attr(not(feature = "json"), allow(broken_intra_doc_links))]
+    HttpResponse {
+        /// The status code of the response.
+        status: StatusCode,
+        /// The response body, which may describe the error. It is truncated to 64 KiB.
+        body: Bytes,
+        /// The value of the `Retry-After` header in seconds, if any.
+        retry_after: Option<Duration>,
+    },
+    /// The server responded with a status code other than `200 OK` and a list of Twitter API
+    /// errors in the body.
+    ///
+    /// Whether the error is [recoverable](Error::is_recoverable) depends on `status` as for
+    /// [`HttpResponse`](Error::HttpResponse), so that it does not depend on the `json` feature.
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    Twitter {
+        /// The status code of the response.
+        status: StatusCode,
+        /// The errors in the response body.
+        errors: Vec<ApiError>,
+        /// The value of the `Retry-After` header in seconds, if any.
+        retry_after: Option<Duration>,
+    },
     /// Error from the underlying HTTP client while receiving an HTTP response or reading the body.
     Service(E),
     /// Twitter returned a non-UTF-8 string.
     Utf8(Utf8Error),
+    /// Failed to initialize the TLS connector in [`Builder::try_listen`].
+    ///
+    /// [`Builder::try_listen`]: crate::Builder::try_listen
+    #[cfg(feature = "tls-native")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tls-native")))]
+    Tls(native_tls::Error),
+    /// The parameters of the request exceed the limits of the Streaming API.
+    ///
+    /// This is returned before sending the request.
+    InvalidParameter(ParameterError),
+    /// Failed to deserialize a JSON message.
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    Json(serde_json::Error),
+    /// The Streaming API sent a disconnect message.
+    ///
+    /// This is only yielded when [`Builder::surface_disconnect`] is enabled.
+    ///
+    /// [`Builder::surface_disconnect`]: crate::Builder::surface_disconnect
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    Disconnect(Disconnect),
+    /// The Streaming API sent no data within the duration set with [`Builder::idle_timeout`],
+    /// or did not respond within the duration set with [`Builder::connect_timeout`].
+    ///
+    /// [`Builder::idle_timeout`]: crate::Builder::idle_timeout
+    /// [`Builder::connect_timeout`]: crate::Builder::connect_timeout
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    TimedOut,
+    /// Failed to copy the body to the writer set with [`TwitterStream::tee`].
+    ///
+    /// The stream stops copying the body after this error, but the stream itself continues.
+    ///
+    /// [`TwitterStream::tee`]: crate::TwitterStream::tee
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    Tee(std::io::Error),
+    /// The Streaming API sent a message longer than the limit set with
+    /// [`Builder::max_line_length`].
+    ///
+    /// The stream ends after this error.
+    ///
+    /// [`Builder::max_line_length`]: crate::Builder::max_line_length
+    LineTooLong,
+    /// Failed to decode a gzip-encoded response body, or the response was not gzip-encoded
+    /// although [`Builder::require_gzip`] is set.
+    ///
+    /// [`Builder::require_gzip`]: crate::Builder::require_gzip
+    #[cfg(feature = "gzip")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "gzip")))]
+    Gzip(std::io::Error),
+}
+
+/// An invalid parameter of [`Builder`](crate::Builder).
+///
+/// The `TooMany*` variants are for a parameter exceeding the limits of the Streaming API and
+/// hold the number of the items that were specified. The other variants hold the offending
+/// value.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ParameterError {
+    /// More than 5,000 user IDs are specified for the `follow` parameter.
+    TooManyFollow(usize),
+    /// More than 400 phrases are specified for the `track` parameter.
+    TooManyTrack(usize),
+    /// More than 25 bounding boxes are specified for the `locations` parameter.
+    TooManyLocations(usize),
+    /// The name of an extra parameter is empty or contains a character other than
+    /// ASCII alphanumerics, `-`, `.`, `_` and `~`.
+    InvalidName(String),
+    /// A language identifier contains a character other than ASCII alphanumerics and hyphens.
+    InvalidLanguage(String),
+    /// A phrase for the `track` parameter contains a comma or is blank.
+    InvalidPhrase(String),
+    /// A PowerTrack account name or stream label is empty or contains a character other than
+    /// ASCII alphanumerics, `-`, `.`, `_` and `~`.
+    InvalidPowerTrack(String),
+}
+
+/// An error object in the response body of a refused connection.
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, serde::Deserialize)]
+#[non_exhaustive]
+pub struct ApiError {
+    /// The [error code][1] (e.g. `32` for "Could not authenticate you").
+    ///
+    /// [1]: https://developer.twitter.com/en/support/twitter-api/error-troubleshooting
+    #[serde(default)]
+    pub code: u32,
+    /// A human-readable description of the error.
+    #[serde(default)]
+    pub message: String,
+}
+
+/// A [disconnect message][1] from the Streaming API.
+///
+/// [1]: https://developer.twitter.com/en/docs/tweets/filter-realtime/guides/streaming-message-types
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, serde::Deserialize)]
+#[non_exhaustive]
+pub struct Disconnect {
+    /// The reason of the disconnection.
+    pub code: DisconnectCode,
+    /// The name of the stream that was disconnected.
+    pub stream_name: String,
+    /// A human-readable description of the reason.
+    pub reason: String,
+}
+
+/// A [stall warning][1] from the Streaming API, sent when the client is falling behind and
+/// at risk of being disconnected.
+///
+/// The Streaming API sends these messages only if the `stall_warnings` parameter is set.
+///
+/// [1]: https://developer.twitter.com/en/docs/tweets/filter-realtime/guides/streaming-message-types
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash, serde::Deserialize)]
+#[non_exhaustive]
+pub struct Warning {
+    /// The code of the warning, e.g. `"FALLING_BEHIND"`.
+    pub code: String,
+    /// A human-readable description of the warning.
+    #[serde(default)]
+    pub message: String,
+    /// How full the queue of the messages to be sent to the client is, in percent.
+    #[serde(default)]
+    pub percent_full: Option<u32>,
+}
+
+/// Status code of a [`Disconnect`] message.
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum DisconnectCode {
+    /// `1`: The feed was shutdown (possibly a machine restart).
+    Shutdown,
+    /// `2`: The same endpoint was connected too many times.
+    DuplicateStream,
+    /// `3`: Control streams was used to close a stream (applies to sitestreams).
+    ControlRequest,
+    /// `4`: The client was reading too slowly and was disconnected by the server.
+    Stall,
+    /// `5`: The client appeared to have initiated a disconnect.
+    Normal,
+    /// `6`: An oauth token was revoked for a user (applies to site and userstreams).
+    TokenRevoked,
+    /// `7`: The same credentials were used to connect a new stream and the oldest was disconnected.
+    AdminLogout,
+    /// `9`: The stream connected with a negative count parameter and was disconnected after
+    /// all backfill was delivered.
+    MaxMessageLimit,
+    /// `10`: An internal issue disconnected the stream.
+    StreamException,
+    /// `11`: An internal issue disconnected the stream.
+    BrokerStall,
+    /// `12`: The host the stream was connected to became overloaded and streams were disconnected
+    /// to balance load. Reconnect as usual.
+    ShedLoad,
+    /// A code not listed above.
+    Unknown(u32),
+}
+
+impl<E> Error<E> {
+    /// Returns `true` if the error is likely to be transient, so that reconnecting to
+    /// the Streaming API may succeed.
+    ///
+    /// This follows Twitter's [reconnection guidance][1]: network errors, timeouts,
+    /// `420 Enhance Your Calm`, `429 Too Many Requests` and `5xx` server errors are recoverable,
+    /// whereas other `4xx` errors (e.g. `401 Unauthorized` or `406 Not Acceptable`), invalid
+    /// parameters and TLS initialization errors are not, since reconnecting with the same
+    /// request fails again. [`Utf8`](Error::Utf8), [`Json`](Error::Json) and
+    /// [`Tee`](Error::Tee) errors do not end the stream, so there is no need to reconnect and
+    /// they are not regarded as recoverable either.
+    ///
+    /// [1]: https://developer.twitter.com/en/docs/tutorials/consuming-streaming-data#reconnecting
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use twitter_stream::error::{Error, StatusCode};
+    ///
+    /// assert!(Error::<()>::Http(StatusCode::SERVICE_UNAVAILABLE).is_recoverable());
+    /// assert!(!Error::<()>::Http(StatusCode::UNAUTHORIZED).is_recoverable());
+    /// assert!(Error::Service(()).is_recoverable());
+    /// ```
+    #[cfg_attr(
+        not(all(feature = "json", feature = "tokio")),
+        allow(broken_intra_doc_links)
+    )]
+    pub fn is_recoverable(&self) -> bool {
+        use crate::Error::*;
+
+        match *self {
+            Http(status) | HttpResponse { status, .. } => is_recoverable_status(status),
+            RateLimited { .. } => true,
+            #[cfg(feature = "json")]
+            Twitter { status, .. } => is_recoverable_status(status),
+            Service(_) => true,
+            Utf8(_) => false,
+            #[cfg(feature = "tls-native")]
+            Tls(_) => false,
+            InvalidParameter(_) => false,
+            #[cfg(feature = "json")]
+            Json(_) => false,
+            #[cfg(feature = "json")]
+            Disconnect(ref d) => d.code.is_recoverable(),
+            #[cfg(feature = "tokio")]
+            TimedOut => true,
+            #[cfg(feature = "tokio")]
+            Tee(_) => false,
+            LineTooLong => true,
+            #[cfg(feature = "gzip")]
+            Gzip(_) => true,
+        }
+    }
+
+    /// Returns the duration to wait before the first attempt to reconnect after the error,
+    /// or `None` if the error is not [recoverable](Error::is_recoverable).
+    ///
+    /// If the response had a `Retry-After` header in seconds, its value is returned, or
+    /// the `backoff` of [`RateLimited`](Error::RateLimited) if that is longer. Otherwise,
+    /// the durations are the initial back-off recommended by Twitter:
+    ///
+    /// - 250 milliseconds for network errors, which should be increased linearly up to
+    ///   16 seconds on consecutive failures.
+    /// - 1 minute for rate limiting (`420`, `429` or the error code `88`) and 5 seconds for other
+    ///   HTTP errors, which should be doubled on consecutive failures up to 320 seconds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use std::time::Duration;
+    ///
+    /// use twitter_stream::error::{Error, StatusCode};
+    ///
+    /// let e = Error::<()>::Http(StatusCode::TOO_MANY_REQUESTS);
+    /// assert_eq!(e.retry_after(), Some(Duration::from_secs(60)));
+    /// ```
+    pub fn retry_after(&self) -> Option<Duration> {
+        use crate::Error::*;
+
+        const NETWORK: Duration = Duration::from_millis(250);
+        const HTTP: Duration = Duration::from_secs(5);
+        const RATE_LIMITED: Duration = Duration::from_secs(60);
+
+        if !self.is_recoverable() {
+            return None;
+        }
+
+        Some(match *self {
+            RateLimited {
+                backoff,
+                retry_after,
+                ..
+            } => retry_after.map_or(backoff, |retry_after| retry_after.max(backoff)),
+            HttpResponse {
+                retry_after: Some(retry_after),
+                ..
+            } => retry_after,
+            #[cfg(feature = "json")]
+            Twitter {
+                retry_after: Some(retry_after),
+                ..
+            } => retry_after,
+            Http(status) | HttpResponse { status, .. } if is_rate_limited_status(status) => {
+                RATE_LIMITED
+            }
+            Http(_) | HttpResponse { .. } => HTTP,
+            #[cfg(feature = "json")]
+            Twitter {
+                status, ref errors, ..
+            } if is_rate_limited_status(status)
+                || errors.iter().any(|e| e.code == ApiError::RATE_LIMITED) =>
+            {
+                RATE_LIMITED
+            }
+            #[cfg(feature = "json")]
+            Twitter { .. } => HTTP,
+            _ => NETWORK,
+        })
+    }
+}
+
+/// Parses the `Retry-After` header of a response, ignoring a value that is not in seconds.
+pub(crate) fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?;
+    value.trim().parse().ok().map(Duration::from_secs)
+}
+
+fn is_recoverable_status(status: StatusCode) -> bool {
+    is_rate_limited_status(status) || status.is_server_error()
+}
+
+pub(crate) fn is_rate_limited_status(status: StatusCode) -> bool {
+    status.as_u16() == 420 || status == StatusCode::TOO_MANY_REQUESTS
 }
 
 impl<E: error::Error + 'static> error::Error for Error<E> {
@@ -22,9 +381,25 @@ impl<E: error::Error + 'static> error::Error for Error<E> {
         use crate::Error::*;
 
         match *self {
-            Http(_) => None,
+            Http(_) | RateLimited { .. } | HttpResponse { .. } => None,
+            #[cfg(feature = "json")]
+            Twitter { .. } => None,
             Service(ref e) => Some(e),
             Utf8(ref e) => Some(e),
+            #[cfg(feature = "tls-native")]
+            Tls(ref e) => Some(e),
+            InvalidParameter(ref e) => Some(e),
+            #[cfg(feature = "json")]
+            Json(ref e) => Some(e),
+            #[cfg(feature = "json")]
+            Disconnect(_) => None,
+            #[cfg(feature = "tokio")]
+            TimedOut => None,
+            #[cfg(feature = "tokio")]
+            Tee(ref e) => Some(e),
+            LineTooLong => None,
+            #[cfg(feature = "gzip")]
+            Gzip(ref e) => Some(e),
         }
     }
 }
@@ -35,8 +410,444 @@ impl<E: Display> Display for Error<E> {
 
         match *self {
             Http(ref code) => write!(f, "HTTP status code: {}", code),
+            RateLimited {
+                backoff, ref body, ..
+            } => {
+                write!(
+                    f,
+                    "rate limited; reconnect after {} seconds",
+                    backoff.as_secs()
+                )?;
+                match std::str::from_utf8(body) {
+                    Ok(body) if !body.is_empty() => write!(f, ": {}", body),
+                    _ => Ok(()),
+                }
+            }
+            HttpResponse {
+                ref status,
+                ref body,
+                ..
+            } => {
+                write!(f, "HTTP status code: {}", status)?;
+                if let Ok(body) = std::str::from_utf8(body) {
+                    write!(f, ": {}", body)?;
+                }
+                Ok(())
+            }
+            #[cfg(feature = "json")]
+            Twitter {
+                ref status,
+                ref errors,
+                ..
+            } => {
+                write!(f, "HTTP status code: {}; errors:", status)?;
+                for e in errors {
+                    write!(f, " {} ({})", e.message, e.code)?;
+                }
+                Ok(())
+            }
             Service(ref e) => write!(f, "HTTP client error: {}", e),
             Utf8(ref e) => Display::fmt(e, f),
+            #[cfg(feature = "tls-native")]
+            Tls(ref e) => write!(f, "TLS error: {}", e),
+            InvalidParameter(ref e) => write!(f, "invalid parameter: {}", e),
+            #[cfg(feature = "json")]
+            Json(ref e) => write!(f, "JSON error: {}", e),
+            #[cfg(feature = "json")]
+            Disconnect(ref d) => write!(f, "disconnected by the server: {}", d.reason),
+            #[cfg(feature = "tokio")]
+            TimedOut => f.write_str("timed out"),
+            #[cfg(feature = "tokio")]
+            Tee(ref e) => write!(f, "failed to copy the stream: {}", e),
+            LineTooLong => f.write_str("message too long"),
+            #[cfg(feature = "gzip")]
+            Gzip(ref e) => write!(f, "gzip error: {}", e),
+        }
+    }
+}
+
+impl ParameterError {
+    pub(crate) const MAX_FOLLOW: usize = 5000;
+    pub(crate) const MAX_TRACK: usize = 400;
+    pub(crate) const MAX_LOCATIONS: usize = 25;
+}
+
+impl error::Error for ParameterError {}
+
+impl Display for ParameterError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match *self {
+            ParameterError::InvalidName(ref name) => {
+                write!(f, "invalid parameter name: {:?}", name)
+            }
+            ParameterError::InvalidLanguage(ref lang) => {
+                write!(f, "invalid language identifier: {:?}", lang)
+            }
+            ParameterError::InvalidPhrase(ref phrase) => {
+                write!(f, "invalid phrase to track: {:?}", phrase)
+            }
+            ParameterError::InvalidPowerTrack(ref s) => {
+                write!(
+                    f,
+                    "invalid PowerTrack account name or stream label: {:?}",
+                    s
+                )
+            }
+            ParameterError::TooManyFollow(n) => write!(
+                f,
+                "{} user IDs to follow exceed the limit of {}",
+                n,
+                ParameterError::MAX_FOLLOW,
+            ),
+            ParameterError::TooManyTrack(n) => write!(
+                f,
+                "{} phrases to track exceed the limit of {}",
+                n,
+                ParameterError::MAX_TRACK,
+            ),
+            ParameterError::TooManyLocations(n) => write!(
+                f,
+                "{} locations exceed the limit of {}",
+                n,
+                ParameterError::MAX_LOCATIONS,
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<E> Error<E> {
+    /// Converts the error from the underlying HTTP client into `F`.
+    pub(crate) fn convert<F: From<E>>(self) -> Error<F> {
+        use crate::Error::*;
+
+        match self {
+            Http(code) => Http(code),
+            RateLimited {
+                backoff,
+                retry_after,
+                body,
+            } => RateLimited {
+                backoff,
+                retry_after,
+                body,
+            },
+            HttpResponse {
+                status,
+                body,
+                retry_after,
+            } => HttpResponse {
+                status,
+                body,
+                retry_after,
+            },
+            #[cfg(feature = "json")]
+            Twitter {
+                status,
+                errors,
+                retry_after,
+            } => Twitter {
+                status,
+                errors,
+                retry_after,
+            },
+            Service(e) => Service(e.into()),
+            Utf8(e) => Utf8(e),
+            #[cfg(feature = "tls-native")]
+            Tls(e) => Tls(e),
+            InvalidParameter(e) => InvalidParameter(e),
+            #[cfg(feature = "json")]
+            Json(e) => Json(e),
+            #[cfg(feature = "json")]
+            Disconnect(d) => Disconnect(d),
+            TimedOut => TimedOut,
+            Tee(e) => Tee(e),
+            LineTooLong => LineTooLong,
+            #[cfg(feature = "gzip")]
+            Gzip(e) => Gzip(e),
+        }
+    }
+}
+
+#[cfg(feature = "gzip")]
+impl<E> Error<Error<E>> {
+    /// Unwraps an error from a [`GzipDecoder`](crate::service::GzipDecoder) body.
+    pub(crate) fn flatten(self) -> Error<E> {
+        use crate::Error::*;
+
+        match self {
+            Http(code) => Http(code),
+            RateLimited {
+                backoff,
+                retry_after,
+                body,
+            } => RateLimited {
+                backoff,
+                retry_after,
+                body,
+            },
+            HttpResponse {
+                status,
+                body,
+                retry_after,
+            } => HttpResponse {
+                status,
+                body,
+                retry_after,
+            },
+            #[cfg(feature = "json")]
+            Twitter {
+                status,
+                errors,
+                retry_after,
+            } => Twitter {
+                status,
+                errors,
+                retry_after,
+            },
+            Service(e) => e,
+            Utf8(e) => Utf8(e),
+            #[cfg(feature = "tls-native")]
+            Tls(e) => Tls(e),
+            InvalidParameter(e) => InvalidParameter(e),
+            #[cfg(feature = "json")]
+            Json(e) => Json(e),
+            #[cfg(feature = "json")]
+            Disconnect(d) => Disconnect(d),
+            #[cfg(feature = "tokio")]
+            TimedOut => TimedOut,
+            #[cfg(feature = "tokio")]
+            Tee(e) => Tee(e),
+            LineTooLong => LineTooLong,
+            Gzip(e) => Gzip(e),
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl Disconnect {
+    /// Parses a JSON string as a disconnect message, returning `None` if it is not one.
+    pub(crate) fn from_json(json: &str) -> Option<Self> {
+        #[derive(serde::Deserialize)]
+        struct Message {
+            disconnect: Disconnect,
+        }
+
+        // Avoid deserializing Tweets, which cannot have a `"disconnect"` key.
+        memchr::memmem::find(json.as_bytes(), b"\"disconnect\"")?;
+        serde_json::from_str::<Message>(json)
+            .ok()
+            .map(|message| message.disconnect)
+    }
+}
+
+#[cfg(feature = "json")]
+impl Warning {
+    /// Parses a JSON string as a stall warning, returning `None` if it is not one.
+    pub(crate) fn from_json(json: &str) -> Option<Self> {
+        #[derive(serde::Deserialize)]
+        struct Message {
+            warning: Warning,
+        }
+
+        // Avoid deserializing Tweets, which cannot have a `"warning"` key.
+        memchr::memmem::find(json.as_bytes(), b"\"warning\"")?;
+        serde_json::from_str::<Message>(json)
+            .ok()
+            .map(|message| message.warning)
+    }
+}
+
+#[cfg(feature = "json")]
+impl ApiError {
+    /// "Rate limit exceeded".
+    const RATE_LIMITED: u32 = 88;
+
+    /// Parses the response body of a refused connection, returning `None` if it does not
+    /// contain an `errors` array.
+    pub(crate) fn from_body(body: &[u8]) -> Option<Vec<Self>> {
+        #[derive(serde::Deserialize)]
+        struct Body {
+            errors: Vec<ApiError>,
         }
+
+        serde_json::from_slice::<Body>(body)
+            .ok()
+            .map(|body| body.errors)
+            .filter(|errors| !errors.is_empty())
+    }
+}
+
+#[cfg(feature = "json")]
+impl DisconnectCode {
+    /// Returns `true` if reconnecting after a disconnection with this code is expected to
+    /// succeed.
+    ///
+    /// This is `false` for `DuplicateStream`, `TokenRevoked` and `AdminLogout`, which indicate
+    /// a problem of the credentials or the other connections, and for `MaxMessageLimit`, after
+    /// which reconnecting would deliver the same backfill again.
+    pub fn is_recoverable(self) -> bool {
+        use DisconnectCode::*;
+
+        !matches!(
+            self,
+            DuplicateStream | TokenRevoked | AdminLogout | MaxMessageLimit
+        )
+    }
+
+    /// Returns the numeric value of the code.
+    pub fn as_u32(self) -> u32 {
+        use DisconnectCode::*;
+
+        match self {
+            Shutdown => 1,
+            DuplicateStream => 2,
+            ControlRequest => 3,
+            Stall => 4,
+            Normal => 5,
+            TokenRevoked => 6,
+            AdminLogout => 7,
+            MaxMessageLimit => 9,
+            StreamException => 10,
+            BrokerStall => 11,
+            ShedLoad => 12,
+            Unknown(code) => code,
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<u32> for DisconnectCode {
+    fn from(code: u32) -> Self {
+        use DisconnectCode::*;
+
+        match code {
+            1 => Shutdown,
+            2 => DuplicateStream,
+            3 => ControlRequest,
+            4 => Stall,
+            5 => Normal,
+            6 => TokenRevoked,
+            7 => AdminLogout,
+            9 => MaxMessageLimit,
+            10 => StreamException,
+            11 => BrokerStall,
+            12 => ShedLoad,
+            code => Unknown(code),
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl<'de> serde::Deserialize<'de> for DisconnectCode {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        u32::deserialize(d).map(From::from)
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warning() {
+        let json = r#"{"warning":{"code":"FALLING_BEHIND","message":"Your connection is falling behind and messages are being queued for delivery to you. Your queue is now over 60% full. You will be disconnected when the queue is full.","percent_full":60}}"#;
+        let warning = Warning::from_json(json).unwrap();
+        assert_eq!(warning.code, "FALLING_BEHIND");
+        assert_eq!(warning.percent_full, Some(60));
+
+        let json = r#"{"id":1,"text":"\"warning\""}"#;
+        assert!(Warning::from_json(json).is_none());
+    }
+
+    #[test]
+    fn disconnect() {
+        let json =
+            r#"{"disconnect":{"code":4,"stream_name":"foo-statuses168","reason":"admin logout"}}"#;
+        let disconnect = Disconnect::from_json(json).unwrap();
+        assert_eq!(disconnect.code, DisconnectCode::Stall);
+        assert_eq!(disconnect.stream_name, "foo-statuses168");
+        assert_eq!(disconnect.reason, "admin logout");
+
+        let json = r#"{"id":1,"text":"\"disconnect\""}"#;
+        assert!(Disconnect::from_json(json).is_none());
+    }
+
+    #[test]
+    fn api_error() {
+        let body = br#"{"errors":[{"code":32,"message":"Could not authenticate you."}]}"#;
+        let errors = ApiError::from_body(body).unwrap();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].code, 32);
+        assert_eq!(errors[0].message, "Could not authenticate you.");
+
+        assert!(ApiError::from_body(b"Unauthorized").is_none());
+        assert!(ApiError::from_body(br#"{"errors":[]}"#).is_none());
+    }
+
+    #[test]
+    fn is_recoverable() {
+        let twitter = |status, code| Error::<()>::Twitter {
+            status,
+            errors: vec![ApiError {
+                code,
+                message: String::new(),
+            }],
+            retry_after: None,
+        };
+
+        assert!(!twitter(StatusCode::UNAUTHORIZED, 32).is_recoverable());
+        assert_eq!(twitter(StatusCode::UNAUTHORIZED, 32).retry_after(), None);
+        // The status code decides regardless of the error codes, as without the `json` feature.
+        let e = twitter(StatusCode::SERVICE_UNAVAILABLE, 130);
+        assert!(e.is_recoverable());
+        assert_eq!(e.retry_after(), Some(Duration::from_secs(5)));
+        let e = Error::<()>::HttpResponse {
+            status: StatusCode::SERVICE_UNAVAILABLE,
+            body: Bytes::new(),
+            retry_after: None,
+        };
+        assert!(e.is_recoverable());
+        assert!(twitter(StatusCode::SERVICE_UNAVAILABLE, 32).is_recoverable());
+        assert!(!twitter(StatusCode::FORBIDDEN, 130).is_recoverable());
+        let e = twitter(StatusCode::SERVICE_UNAVAILABLE, 88);
+        assert_eq!(e.retry_after(), Some(Duration::from_secs(60)));
+
+        let enhance_your_calm = StatusCode::from_u16(420).unwrap();
+        let e = Error::<()>::HttpResponse {
+            status: enhance_your_calm,
+            body: Bytes::new(),
+            retry_after: None,
+        };
+        assert_eq!(e.retry_after(), Some(Duration::from_secs(60)));
+        assert!(!Error::<()>::Http(StatusCode::NOT_ACCEPTABLE).is_recoverable());
+        assert_eq!(
+            Error::Service(()).retry_after(),
+            Some(Duration::from_millis(250)),
+        );
+
+        let rate_limited = |retry_after| Error::<()>::RateLimited {
+            backoff: Duration::from_secs(60),
+            retry_after: Some(Duration::from_secs(retry_after)),
+            body: Bytes::new(),
+        };
+        assert_eq!(
+            rate_limited(120).retry_after(),
+            Some(Duration::from_secs(120)),
+        );
+        assert_eq!(
+            rate_limited(10).retry_after(),
+            Some(Duration::from_secs(60))
+        );
+
+        // The stream continues after these errors.
+        let invalid = vec![0xFF];
+        let e = Error::<()>::Utf8(std::str::from_utf8(&invalid).unwrap_err());
+        assert!(!e.is_recoverable());
+        let e = Error::<()>::Json(serde_json::from_str::<()>("{").unwrap_err());
+        assert!(!e.is_recoverable());
+
+        assert!(DisconnectCode::Stall.is_recoverable());
+        assert!(!DisconnectCode::DuplicateStream.is_recoverable());
     }
 }
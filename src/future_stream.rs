@@ -0,0 +1,157 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use futures_core::{ready, Stream};
+use http::Response;
+use http_body::Body;
+
+use crate::{Error, FutureTwitterStream};
+
+impl<F, B, E> Stream for FutureTwitterStream<F>
+where
+    F: Future<Output = Result<Response<B>, E>>,
+    B: Body<Error = E>,
+{
+    type Item = Result<string::String<Bytes>, Error<E>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if let Some(stream) = self.as_mut().project().stream.as_pin_mut() {
+            return stream.poll_next(cx);
+        }
+
+        let this = self.as_mut().project();
+        if this.response.is_none() && this.erroring.is_none() && this.invalid_parameter.is_none() {
+            // The connection has failed.
+            return Poll::Ready(None);
+        }
+
+        let result = ready!(self.as_mut().poll(cx));
+        let mut this = self.project();
+        this.response.set(None);
+        match result {
+            Ok(stream) => {
+                this.stream.set(Some(stream));
+                this.stream.as_pin_mut().unwrap().poll_next(cx)
+            }
+            Err(e) => Poll::Ready(Some(Err(e))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use futures::executor::block_on_stream;
+    use futures::future;
+    use http::StatusCode;
+
+    use super::*;
+    use crate::Config;
+
+    fn stream(res: Response<hyper_pkg::Body>) -> Vec<Result<String, Error<hyper_pkg::Error>>> {
+        let future = FutureTwitterStream {
+            response: Some(future::ok(res)),
+            invalid_parameter: None,
+            erroring: None,
+            connect_timeout: None,
+            stream: None,
+            config: Config::default(),
+        };
+        block_on_stream(future)
+            .map(|result| result.map(|s| s.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn messages() {
+        let res = Response::new(hyper_pkg::Body::from("{\"id\":1}\r\n{\"id\":2}\r\n"));
+        let items = stream(res);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[1].as_ref().unwrap(), "{\"id\":2}");
+
+        let res = Response::builder()
+            .status(StatusCode::UNAUTHORIZED)
+            .body(hyper_pkg::Body::empty())
+            .unwrap();
+        let items = stream(res);
+        assert_eq!(items.len(), 1);
+        assert!(matches!(
+            items[0],
+            Err(Error::Http(StatusCode::UNAUTHORIZED))
+        ));
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn error_body() {
+        let res = Response::builder()
+            .status(StatusCode::UNAUTHORIZED)
+            .body(hyper_pkg::Body::from(
+                r#"{"errors":[{"code":32,"message":"Could not authenticate you."}]}"#,
+            ))
+            .unwrap();
+        let items = stream(res);
+        assert_eq!(items.len(), 1);
+        match items[0] {
+            Err(Error::Twitter {
+                status, ref errors, ..
+            }) => {
+                assert_eq!(status, StatusCode::UNAUTHORIZED);
+                assert_eq!(errors[0].code, 32);
+            }
+            ref item => panic!("unexpected item: {:?}", item),
+        }
+    }
+
+    #[test]
+    fn error_response() {
+        let res = Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(hyper_pkg::Body::from("Not Found"))
+            .unwrap();
+        let items = stream(res);
+        assert_eq!(items.len(), 1);
+        match items[0] {
+            Err(Error::HttpResponse {
+                status: StatusCode::NOT_FOUND,
+                ref body,
+                retry_after: None,
+            }) => assert_eq!(body, "Not Found"),
+            ref item => panic!("unexpected item: {:?}", item),
+        }
+    }
+
+    #[test]
+    fn retry_after() {
+        let res = Response::builder()
+            .status(StatusCode::SERVICE_UNAVAILABLE)
+            .header("retry-after", "120")
+            .body(hyper_pkg::Body::empty())
+            .unwrap();
+        let items = stream(res);
+        assert_eq!(items.len(), 1);
+        match items[0] {
+            Err(ref e @ Error::HttpResponse { .. }) => {
+                assert_eq!(e.retry_after(), Some(Duration::from_secs(120)))
+            }
+            ref item => panic!("unexpected item: {:?}", item),
+        }
+
+        // An HTTP date is ignored.
+        let res = Response::builder()
+            .status(StatusCode::SERVICE_UNAVAILABLE)
+            .header("retry-after", "Fri, 31 Dec 1999 23:59:59 GMT")
+            .body(hyper_pkg::Body::empty())
+            .unwrap();
+        let items = stream(res);
+        match items[0] {
+            Err(ref e @ Error::Http(StatusCode::SERVICE_UNAVAILABLE)) => {
+                assert_eq!(e.retry_after(), Some(Duration::from_secs(5)))
+            }
+            ref item => panic!("unexpected item: {:?}", item),
+        }
+    }
+}
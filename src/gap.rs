@@ -0,0 +1,211 @@
+use std::time::Duration;
+
+/// Estimates the number of messages missed in a stream from its Tweet IDs and [limit notices].
+///
+/// Pass the ID of each Tweet to [`tweet`](GapDetector::tweet) and the `track` value of each
+/// limit notice to [`limit`](GapDetector::limit), in the order they are received. Each method
+/// returns a [`Gap`] if messages seem to have been missed right before the message.
+///
+/// A gap is detected in two ways:
+///
+/// - A limit notice reports the number of Tweets that were not delivered since the previous
+///   one. This count comes from Twitter, and the `Gap` is marked as `reported`.
+/// - The time between two consecutive Tweets, read from the timestamp in their IDs, is at
+///   least `min_gap`. The number of missed Tweets is then estimated from the mean interval
+///   between the Tweets seen so far, excluding the gaps.
+///
+/// The estimate from the IDs is only a hint. The IDs are not sequential and only roughly
+/// ordered, and the estimate assumes that Tweets arrive at a steady rate. A quiet period of
+/// a low-volume stream looks just like missed Tweets, so choose a `min_gap` well above
+/// the usual interval between Tweets. A Tweet older than the latest one is ignored for
+/// the estimate.
+///
+/// [limit notices]: https://developer.twitter.com/en/docs/tweets/filter-realtime/guides/streaming-message-types
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use twitter_stream::GapDetector;
+///
+/// let mut detector = GapDetector::new(Duration::from_secs(60));
+/// let messages = [r#"{"id":1}"#, r#"{"limit":{"track":10}}"#];
+/// let gaps: Vec<_> = messages
+///     .iter()
+///     .map(|json| {
+///         let message: serde_json::Value = serde_json::from_str(json).unwrap();
+///         if let Some(track) = message["limit"]["track"].as_u64() {
+///             detector.limit(track)
+///         } else if let Some(id) = message["id"].as_u64() {
+///             detector.tweet(id)
+///         } else {
+///             None
+///         }
+///     })
+///     .map(|gap| gap.map(|gap| gap.estimated_missed))
+///     .collect();
+/// assert_eq!(gaps, [None, Some(10)]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct GapDetector {
+    // The `track` value of the last limit notice.
+    track: u64,
+    // The latest timestamp in milliseconds read from a Tweet ID.
+    last_time: Option<u64>,
+    // The sum of the intervals between Tweets in milliseconds, excluding the gaps.
+    elapsed: u64,
+    // The number of the intervals summed in `elapsed`.
+    intervals: u64,
+    min_gap: u64,
+}
+
+/// A gap in a stream detected by [`GapDetector`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct Gap {
+    /// The estimated number of messages missed before the message.
+    pub estimated_missed: u64,
+    /// Whether the gap was reported by a limit notice, in which case `estimated_missed` is
+    /// the number of undelivered Tweets counted by Twitter. Otherwise, the gap is estimated
+    /// from the Tweet IDs.
+    pub reported: bool,
+}
+
+impl GapDetector {
+    /// Creates a `GapDetector` that estimates a gap from the Tweet IDs when two consecutive
+    /// Tweets are at least `min_gap` apart.
+    pub fn new(min_gap: Duration) -> Self {
+        GapDetector {
+            track: 0,
+            last_time: None,
+            elapsed: 0,
+            intervals: 0,
+            min_gap: min_gap.as_millis().min(u64::MAX.into()) as u64,
+        }
+    }
+
+    /// Records a Tweet with the given ID, returning the gap estimated before it, if any.
+    pub fn tweet(&mut self, id: u64) -> Option<Gap> {
+        // The upper bits of a Snowflake ID are the timestamp in milliseconds.
+        let time = id >> 22;
+        let interval = match self.last_time {
+            // The IDs are only roughly ordered, so ignore a Tweet older than the latest one.
+            Some(last_time) if time < last_time => return None,
+            Some(last_time) => time - last_time,
+            None => {
+                self.last_time = Some(time);
+                return None;
+            }
+        };
+        self.last_time = Some(time);
+
+        if interval >= self.min_gap && self.intervals > 0 {
+            // Assume that the Tweets are evenly spaced in time.
+            let mean = (self.elapsed / self.intervals).max(1);
+            let missed = (interval / mean).saturating_sub(1);
+            if missed > 0 {
+                return Some(Gap {
+                    estimated_missed: missed,
+                    reported: false,
+                });
+            }
+        }
+        self.elapsed += interval;
+        self.intervals += 1;
+        None
+    }
+
+    /// Records a limit notice with the given `track` value, returning the gap it reports,
+    /// if any.
+    pub fn limit(&mut self, track: u64) -> Option<Gap> {
+        let missed = track.saturating_sub(self.track);
+        self.track = track.max(self.track);
+        if missed > 0 {
+            Some(Gap {
+                estimated_missed: missed,
+                reported: true,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Tells the `GapDetector` that the stream has reconnected.
+    ///
+    /// The `track` values of limit notices restart on every connection, so call this before
+    /// passing the messages of a new connection.
+    pub fn reconnect(&mut self) {
+        self.track = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Makes a Tweet ID with the timestamp of `time` milliseconds.
+    fn id(time: u64) -> u64 {
+        time << 22 | 1
+    }
+
+    fn gaps(times: &[u64], min_gap: Duration) -> Vec<Option<u64>> {
+        let mut detector = GapDetector::new(min_gap);
+        times
+            .iter()
+            .map(|&time| detector.tweet(id(time)).map(|gap| gap.estimated_missed))
+            .collect()
+    }
+
+    #[test]
+    fn id_jump() {
+        // Tweets every second, then nothing for 10 seconds.
+        assert_eq!(
+            gaps(
+                &[0, 1000, 2000, 3000, 13_000, 14_000],
+                Duration::from_secs(5)
+            ),
+            [None, None, None, None, Some(9), None],
+        );
+
+        // The gap is not counted in the mean interval.
+        assert_eq!(
+            gaps(&[0, 1000, 11_000, 12_000, 22_000], Duration::from_secs(5)),
+            [None, None, Some(9), None, Some(9)],
+        );
+    }
+
+    #[test]
+    fn id_jump_below_min_gap() {
+        assert_eq!(
+            gaps(&[0, 1000, 4000], Duration::from_secs(5)),
+            [None, None, None],
+        );
+    }
+
+    #[test]
+    fn id_out_of_order() {
+        // A Tweet older than the latest one is neither a gap nor moves the clock back.
+        assert_eq!(
+            gaps(&[0, 1000, 500, 2000, 12_000], Duration::from_secs(5)),
+            [None, None, None, None, Some(9)],
+        );
+    }
+
+    #[test]
+    fn limit() {
+        let mut detector = GapDetector::new(Duration::from_secs(5));
+        let gap = |missed| {
+            Some(Gap {
+                estimated_missed: missed,
+                reported: true,
+            })
+        };
+        assert_eq!(detector.limit(10), gap(10));
+        assert_eq!(detector.limit(10), None);
+        assert_eq!(detector.limit(25), gap(15));
+
+        detector.reconnect();
+        assert_eq!(detector.limit(5), gap(5));
+    }
+}
@@ -49,13 +49,28 @@ contains exactly one JSON value.
 The underlying Streaming API [sends a blank line][stalls] every 30 seconds as a "keep-alive" signal,
 but `TwitterStream` discards it so that you can always expect to yield a valid JSON string.
 On the other hand, this means that you cannot use the blank line to set a timeout on `Stream`-level.
-If you want the stream to time out on network stalls, set a timeout on the underlying
+If you want the stream to time out on network stalls, use [`Builder::idle_timeout`] (requires the
+`tokio` feature), which takes the blank lines into account, or set a timeout on the underlying
 HTTP connector, instead of the `Stream` (see the [`timeout` example] in the crate's repository
 for details).
 
 [stalls]: https://developer.twitter.com/en/docs/tweets/filter-realtime/guides/connecting#stalls
 [`timeout` example]: https://github.com/tesaguri/twitter-stream-rs/blob/v0.13.0/examples/timeout.rs
 
+[`Builder::listen`] connects to the Streaming API over HTTPS with `native-tls` by default
+(the `hyper` feature, which enables `tls-native`). To use `rustls` instead, e.g. for
+cross-compiling to a target without OpenSSL, disable the default features and enable
+the `tls-rustls` feature. Only one of the two TLS features should be enabled. The
+`hyper-client` feature enables [`Builder::listen_with_connector`] without either of them.
+
+With the `gzip` feature enabled, `Builder` requests the `gzip` encoding and `TwitterStream`
+decodes a response body with a `content-encoding: gzip` header regardless of the HTTP client
+being used (see [`service::GzipDecoder`]).
+
+With the `reqwest` feature enabled, the [`reqwest`] module provides an
+[`HttpService`](service::HttpService) implementation using `reqwest`'s HTTP client, which can be
+passed to [`Builder::listen_with_client`].
+
 The JSON string usually, but not always, represents a [Tweet] object. When deserializing the JSON
 string, you should be able to handle any kind of JSON value. A possible implementation of
 deserialization would be like the following:
@@ -79,14 +94,67 @@ struct Tweet { /* ... */ }
 The [`echo_bot` example] in the crate's repository shows an example of a `StreamMessage`
 implementation.
 
+With the `json` feature enabled, [`TwitterStream::parse`] deserializes the JSON strings into
+such a type for you.
+
+With the `chrono` feature enabled, [`parse_twitter_time`] parses the timestamps like
+the `created_at` field of Tweets.
+
 [`echo_bot` example]: https://github.com/tesaguri/twitter-stream-rs/blob/v0.13.0/examples/echo_bot.rs
 
 See the [Twitter Developers Documentation][message-types] for the types and formats of the JSON
 messages.
 
 [message-types]: https://developer.twitter.com/en/docs/tweets/filter-realtime/guides/streaming-message-types
+
+## Ordering and completeness of messages
+
+`TwitterStream` yields the messages in the order they are received, but the Streaming API itself
+does not guarantee that Tweets are delivered in chronological order, nor that every matching Tweet
+is delivered. The messages carry no sequence number, so there is no exact way to tell whether
+a message was missed.
+
+When a filtered stream matches more Tweets than it is allowed to deliver, the API sends
+a [limit notice] (`{"limit":{"track":...}}`) whose `track` value is the total number of undelivered
+Tweets since the connection was opened. The difference between two consecutive `track` values
+is the number of Tweets missed in between. Note that the count restarts on every reconnection.
+
+Tweet IDs are not sequential either, but their upper bits are the creation time of the Tweet.
+[`GapDetector`] combines the limit notices with a rough estimate from the time between
+consecutive Tweet IDs.
+
+[limit notice]: https://developer.twitter.com/en/docs/tweets/filter-realtime/guides/streaming-message-types
 */
 
+// Link the items of the disabled optional features to docs.rs instead.
+#![cfg_attr(
+    not(any(feature = "tls-native", feature = "tls-rustls")),
+    doc = "[`Builder::listen`]: https://docs.rs/twitter-stream/0.13.0/twitter_stream/struct.Builder.html#method.listen"
+)]
+#![cfg_attr(
+    not(feature = "hyper-client"),
+    doc = "[`Builder::listen_with_connector`]: https://docs.rs/twitter-stream/0.13.0/twitter_stream/struct.Builder.html#method.listen_with_connector"
+)]
+#![cfg_attr(
+    not(feature = "tokio"),
+    doc = "[`Builder::idle_timeout`]: https://docs.rs/twitter-stream/0.13.0/twitter_stream/struct.Builder.html#method.idle_timeout"
+)]
+#![cfg_attr(
+    not(feature = "gzip"),
+    doc = "[`service::GzipDecoder`]: https://docs.rs/twitter-stream/0.13.0/twitter_stream/service/struct.GzipDecoder.html"
+)]
+#![cfg_attr(
+    not(feature = "reqwest"),
+    doc = "[`reqwest`]: https://docs.rs/twitter-stream/0.13.0/twitter_stream/reqwest/index.html"
+)]
+#![cfg_attr(
+    not(feature = "json"),
+    doc = "[`TwitterStream::parse`]: https://docs.rs/twitter-stream/0.13.0/twitter_stream/struct.TwitterStream.html#method.parse"
+)]
+#![cfg_attr(
+    not(feature = "chrono"),
+    doc = "[`parse_twitter_time`]: https://docs.rs/twitter-stream/0.13.0/twitter_stream/fn.parse_twitter_time.html"
+)]
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![deny(broken_intra_doc_links)]
 #![warn(missing_docs)]
@@ -98,27 +166,70 @@ mod doctest;
 mod util;
 
 pub mod builder;
+mod close;
+#[cfg(feature = "chrono")]
+mod datetime;
 pub mod error;
-#[cfg(feature = "hyper")]
-#[cfg_attr(docsrs, doc(cfg(feature = "hyper")))]
+mod future_stream;
+mod gap;
+#[cfg(feature = "hyper-client")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hyper-client")))]
 pub mod hyper;
+#[cfg(feature = "json")]
+mod parsed;
+#[cfg(feature = "tokio")]
+mod prefetch;
+#[cfg(feature = "tokio")]
+mod reader;
+mod reconfigurable;
+#[cfg(feature = "reqwest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "reqwest")))]
+pub mod reqwest;
+#[cfg(feature = "json")]
+mod rest;
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+pub mod rules;
+#[cfg(feature = "serde")]
+#[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+pub mod serde_token;
 pub mod service;
+mod stream_set;
 
 #[doc(no_inline)]
 pub use oauth_credentials::Credentials;
 
 pub use crate::builder::Builder;
+pub use crate::close::Close;
+#[cfg(feature = "chrono")]
+#[cfg_attr(docsrs, doc(cfg(feature = "chrono")))]
+pub use crate::datetime::parse_twitter_time;
 pub use crate::error::Error;
+pub use crate::gap::{Gap, GapDetector};
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+pub use crate::parsed::{Dedup, ParsedStream, TrackLimit, Tweets, Validated, WithRaw};
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub use crate::prefetch::Prefetch;
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+pub use crate::reader::ReaderBody;
+pub use crate::reconfigurable::Reconfigurable;
+pub use crate::stream_set::StreamSet;
 
 use std::future::Future;
+use std::mem;
 use std::pin::Pin;
 use std::str;
 use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
 
-use bytes::Bytes;
+use bytes::{Buf, Bytes};
 use futures_core::{ready, Stream};
-use http::Response;
-use http::StatusCode;
+#[cfg(feature = "tokio")]
+use futures_util::future::{AbortHandle, Abortable};
+use http::{HeaderMap, Response, StatusCode};
 use http_body::Body;
 use pin_project_lite::pin_project;
 
@@ -126,9 +237,54 @@ use crate::util::Lines;
 
 pin_project! {
     /// A future returned by constructor methods which resolves to a [`TwitterStream`].
-    pub struct FutureTwitterStream<F> {
+    ///
+    /// `FutureTwitterStream` is also a `Stream`, which connects to the Streaming API and then
+    /// yields the messages from the API, so that you do not have to wait for the future before
+    /// reading the messages. A connection error is yielded as the first and last item of
+    /// the stream.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use futures::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let token = twitter_stream::Token::from_parts("", "", "", "");
+    /// let mut stream = twitter_stream::Builder::new(token).track("@Twitter").listen();
+    ///
+    /// while let Some(json) = stream.next().await {
+    ///     println!("{}", json.unwrap());
+    /// }
+    /// # }
+    /// ```
+    pub struct FutureTwitterStream<F: ResponseFuture> {
+        #[pin]
+        response: Option<F>,
+        // Set if the request was not sent because of invalid parameters.
+        invalid_parameter: Option<error::ParameterError>,
+        // Set if the response has a non-200 status.
         #[pin]
-        response: F,
+        erroring: Option<ErroringResponse<ResponseBody<F::Body>>>,
+        // Created on the first poll if `config.connect_timeout` is set.
+        connect_timeout: Option<Sleep>,
+        // Set when the future is polled as a `Stream` and the connection succeeds.
+        #[pin]
+        stream: Option<TwitterStream<F::Body>>,
+        config: Config,
+    }
+}
+
+pin_project! {
+    /// A response with a non-200 status whose body is being read.
+    struct ErroringResponse<B> {
+        #[pin]
+        body: B,
+        status: StatusCode,
+        // `Some` if the response is rate-limited.
+        backoff: Option<Duration>,
+        retry_after: Option<Duration>,
+        buf: Vec<u8>,
     }
 }
 
@@ -136,15 +292,639 @@ pin_project! {
     /// A listener for Twitter Streaming API, yielding JSON strings returned from the API.
     pub struct TwitterStream<B> {
         #[pin]
-        inner: Lines<B>,
+        inner: Lines<ResponseBody<B>>,
+        headers: HeaderMap,
+        config: Config,
+        connected_at: Instant,
+        keep_alive_count: u64,
+        message_count: u64,
+    }
+}
+
+use private::ResponseFuture;
+
+#[cfg(feature = "tokio")]
+type Sleep = Pin<Box<tokio::time::Sleep>>;
+// `pin_project!` does not accept `#[cfg]` on fields, so `FutureTwitterStream` has
+// the `connect_timeout` field regardless of the feature.
+#[cfg(not(feature = "tokio"))]
+enum Sleep {}
+
+#[cfg(feature = "gzip")]
+type ResponseBody<B> = service::GzipDecoder<B>;
+#[cfg(not(feature = "gzip"))]
+type ResponseBody<B> = B;
+
+/// Options of [`TwitterStream`] which are set with [`Builder`].
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Config {
+    pub delimited: bool,
+    #[cfg(feature = "http2")]
+    pub http2_only: bool,
+    pub line_buffer_capacity: usize,
+    pub rate_limit_counter: Option<builder::RateLimitCounter>,
+    pub skip_invalid: bool,
+    pub max_line_length: Option<usize>,
+    #[cfg(feature = "gzip")]
+    pub require_gzip: bool,
+    #[cfg(feature = "json")]
+    pub surface_disconnect: bool,
+    #[cfg(feature = "json")]
+    pub on_warning: Option<builder::WarningCallback>,
+    #[cfg(feature = "tokio")]
+    pub idle_timeout: Option<std::time::Duration>,
+    #[cfg(feature = "tokio")]
+    pub connect_timeout: Option<std::time::Duration>,
+}
+
+impl PartialEq for Config {
+    fn eq(&self, other: &Self) -> bool {
+        #[allow(unused_mut)]
+        let mut eq = self.delimited == other.delimited
+            && self.line_buffer_capacity == other.line_buffer_capacity
+            && self.rate_limit_counter == other.rate_limit_counter
+            && self.skip_invalid == other.skip_invalid
+            && self.max_line_length == other.max_line_length;
+        #[cfg(feature = "http2")]
+        {
+            eq &= self.http2_only == other.http2_only;
+        }
+        #[cfg(feature = "gzip")]
+        {
+            eq &= self.require_gzip == other.require_gzip;
+        }
+        #[cfg(feature = "json")]
+        {
+            eq &= self.surface_disconnect == other.surface_disconnect
+                && self.on_warning == other.on_warning;
+        }
+        #[cfg(feature = "tokio")]
+        {
+            eq &= self.idle_timeout == other.idle_timeout
+                && self.connect_timeout == other.connect_timeout;
+        }
+        eq
     }
 }
 
+impl Eq for Config {}
+
+/// Statistics of a [`TwitterStream`], returned by [`TwitterStream::stats`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct StreamStats {
+    /// The time elapsed since the connection was established.
+    pub age: Duration,
+    /// The number of bytes read from the response body (after decoding gzip).
+    pub bytes_read: u64,
+    /// The number of messages yielded so far.
+    pub message_count: u64,
+    /// The number of keep-alive blank lines received so far.
+    pub keep_alive_count: u64,
+}
+
 /// A set of OAuth client credentials and token credentials used for authorizing requests
 /// to the Streaming API.
 pub type Token<C = String, T = String> = oauth_credentials::Token<C, T>;
 
+/// A bearer token used for authorizing requests to the Streaming API with
+/// [OAuth 2.0 application-only authentication][1].
+///
+/// [1]: https://developer.twitter.com/en/docs/authentication/oauth-2-0/application-only
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BearerToken<T = String>(pub T);
+
+/// Credentials used for authorizing requests to the Streaming API, which can be either
+/// OAuth 1.0a user context credentials, a bearer token or HTTP Basic credentials.
+///
+/// This is useful when the authentication method is chosen at runtime, e.g. from a configuration
+/// file. If it is known statically, you can use [`Token`] or [`BearerToken`] directly instead.
+///
+/// # Example
+///
+/// ```
+/// use twitter_stream::Auth;
+///
+/// let req = twitter_stream::Builder::new(Auth::Bearer("bearer_token")).into_request();
+/// assert_eq!(req.headers()["authorization"], "Bearer bearer_token");
+/// ```
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub enum Auth<T = String> {
+    /// OAuth 1.0a user context credentials.
+    OAuth1(Token<T, T>),
+    /// An OAuth 2.0 bearer token.
+    Bearer(T),
+    /// A username and a password for HTTP Basic authentication, which is used by the enterprise
+    /// endpoints like [PowerTrack](crate::Builder::powertrack).
+    Basic {
+        /// The username.
+        username: T,
+        /// The password.
+        password: T,
+    },
+}
+
 impl<B: Body> TwitterStream<B> {
+    /// Creates a `TwitterStream` from the body of a response from the Streaming API.
+    ///
+    /// This is useful if you send the request yourself, e.g. the one made by
+    /// [`Builder::into_request`]. Unlike [`FutureTwitterStream`], this does not check the status
+    /// code of the response, so you should check it before calling this.
+    ///
+    /// The [`headers`](TwitterStream::headers) of a `TwitterStream` created with this method
+    /// are empty.
+    pub fn new(body: B) -> Self {
+        #[cfg(feature = "gzip")]
+        let body = service::GzipDecoder::from_headers(&HeaderMap::new(), body);
+        TwitterStream {
+            inner: Lines::new(body),
+            headers: HeaderMap::new(),
+            config: Config::default(),
+            connected_at: Instant::now(),
+            keep_alive_count: 0,
+            message_count: 0,
+        }
+    }
+
+    /// Deserializes the messages from the Streaming API as `T`.
+    ///
+    /// A message that fails to deserialize is yielded as [`Error::Json`], which does not
+    /// terminate the stream, or skipped if [`Builder::skip_invalid`] is set.
+    ///
+    /// Since `T` must be `DeserializeOwned`, `T` cannot borrow from the JSON string.
+    /// If you want to deserialize borrowing types, deserialize the strings yielded from
+    /// the `TwitterStream` by yourself and keep the strings alive while using the messages.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use twitter_stream::TwitterStream;
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct Tweet {
+    ///     id: u64,
+    ///     text: String,
+    /// }
+    ///
+    /// # futures::executor::block_on(async {
+    /// let body = hyper_pkg::Body::from("{\"id\":1,\"text\":\"Hello\"}\r\n");
+    /// let mut tweets = TwitterStream::new(body).parse::<Tweet>();
+    ///
+    /// let tweet = tweets.try_next().await.unwrap().unwrap();
+    /// assert_eq!(tweet.id, 1);
+    /// assert_eq!(tweet.text, "Hello");
+    /// # });
+    /// ```
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn parse<T: serde::de::DeserializeOwned>(self) -> ParsedStream<B, T> {
+        ParsedStream::new(self)
+    }
+
+    /// Deserializes the messages from the Streaming API as `T`, yielding each message together
+    /// with the raw JSON string it was deserialized from.
+    ///
+    /// This is useful for logging or archiving the exact JSON sent by the API, including
+    /// the messages that fail to deserialize, which are yielded with an `Err` in place of `T`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use twitter_stream::TwitterStream;
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct Tweet {
+    ///     id: u64,
+    /// }
+    ///
+    /// # futures::executor::block_on(async {
+    /// let body = hyper_pkg::Body::from("{\"id\":1}\r\n{\"limit\":{\"track\":1}}\r\n");
+    /// let mut messages = TwitterStream::new(body).with_raw::<Tweet>();
+    ///
+    /// let (raw, tweet) = messages.try_next().await.unwrap().unwrap();
+    /// assert_eq!(&*raw, "{\"id\":1}");
+    /// assert_eq!(tweet.unwrap().id, 1);
+    ///
+    /// let (raw, tweet) = messages.try_next().await.unwrap().unwrap();
+    /// assert_eq!(&*raw, "{\"limit\":{\"track\":1}}");
+    /// assert!(tweet.is_err());
+    /// # });
+    /// ```
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn with_raw<T: serde::de::DeserializeOwned>(self) -> WithRaw<B, T> {
+        WithRaw::new(self)
+    }
+
+    /// Deserializes the messages from the Streaming API as `T`, skipping the messages that fail
+    /// to deserialize.
+    ///
+    /// This is for the common case where only the Tweets are of interest. With a Tweet type
+    /// as `T`, the other messages like deletion notices, limit notices and stall warnings are
+    /// skipped without having to define an enum of all the message types. Unlike
+    /// [`parse`](TwitterStream::parse), the stream yields errors only from the connection, so
+    /// a Tweet that `T` fails to deserialize (e.g. because of a missing field) is silently
+    /// dropped. Use `parse` or [`with_raw`](TwitterStream::with_raw) to find such mistakes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use twitter_stream::TwitterStream;
+    ///
+    /// #[derive(serde::Deserialize)]
+    /// struct Tweet {
+    ///     id: u64,
+    ///     text: String,
+    /// }
+    ///
+    /// # futures::executor::block_on(async {
+    /// let body = hyper_pkg::Body::from(concat!(
+    ///     "{\"id\":1,\"text\":\"Hello\"}\r\n",
+    ///     "{\"delete\":{\"status\":{\"id\":1,\"user_id\":2}}}\r\n",
+    ///     "{\"limit\":{\"track\":1}}\r\n",
+    ///     "{\"id\":3,\"text\":\"World\"}\r\n",
+    /// ));
+    /// let ids: Vec<u64> = TwitterStream::new(body)
+    ///     .tweets::<Tweet>()
+    ///     .map_ok(|tweet| tweet.id)
+    ///     .try_collect()
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(ids, [1, 3]);
+    /// # });
+    /// ```
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn tweets<T: serde::de::DeserializeOwned>(self) -> Tweets<B, T> {
+        Tweets::new(self)
+    }
+
+    /// Yields each message along with the number of Tweets that the Streaming API reported as
+    /// undelivered since the previous [limit notice], or `None` if the message is not a limit
+    /// notice.
+    ///
+    /// The `track` value of a limit notice is the total number of undelivered Tweets since
+    /// the connection was opened, so this computes the difference between consecutive notices,
+    /// which is handy for alerting on spikes of undelivered Tweets.
+    ///
+    /// [limit notice]: https://developer.twitter.com/en/docs/tweets/filter-realtime/guides/streaming-message-types
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use twitter_stream::TwitterStream;
+    ///
+    /// # futures::executor::block_on(async {
+    /// let body = hyper_pkg::Body::from(
+    ///     "{\"limit\":{\"track\":10}}\r\n{\"id\":1}\r\n{\"limit\":{\"track\":25}}\r\n",
+    /// );
+    /// let deltas: Vec<_> = TwitterStream::new(body)
+    ///     .track_limit()
+    ///     .map_ok(|(_, delta)| delta)
+    ///     .try_collect()
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(deltas, [Some(10), None, Some(15)]);
+    /// # });
+    /// ```
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn track_limit(self) -> TrackLimit<B> {
+        TrackLimit::new(self)
+    }
+
+    /// Suppresses the Tweets whose IDs are among the last `capacity` IDs seen, which the Streaming
+    /// API may redeliver after a reconnect.
+    ///
+    /// The ID is read from the top-level `id` field without deserializing the rest of the message,
+    /// and the messages without the field, like delete notices, are yielded as-is. To carry
+    /// the remembered IDs over to a new connection, pass it to
+    /// [`Dedup::replace_inner`](crate::Dedup::replace_inner).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use twitter_stream::TwitterStream;
+    ///
+    /// # futures::executor::block_on(async {
+    /// let body = hyper_pkg::Body::from("{\"id\":1}\r\n{\"id\":2}\r\n{\"id\":1}\r\n");
+    /// let mut stream = TwitterStream::new(body).dedup(100);
+    /// let lines: Vec<String> = (&mut stream)
+    ///     .map_ok(|s| s.to_string())
+    ///     .try_collect()
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(lines, ["{\"id\":1}", "{\"id\":2}"]);
+    ///
+    /// // Reconnected.
+    /// let body = hyper_pkg::Body::from("{\"id\":2}\r\n{\"id\":3}\r\n");
+    /// stream.replace_inner(TwitterStream::new(body));
+    /// let lines: Vec<String> = stream.map_ok(|s| s.to_string()).try_collect().await.unwrap();
+    /// assert_eq!(lines, ["{\"id\":3}"]);
+    /// # });
+    /// ```
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn dedup(self, capacity: usize) -> Dedup<B> {
+        Dedup::new(self, capacity)
+    }
+
+    /// Returns the headers of the HTTP response from the Streaming API.
+    ///
+    /// This is useful for inspecting headers like `x-connection-hash`,
+    /// which identifies the connection when you contact Twitter about a disconnection.
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Checks that each message from the Streaming API is valid JSON, yielding the raw JSON
+    /// strings.
+    ///
+    /// This catches a truncated or corrupt message as an [`Error::Json`] without the cost of
+    /// deserializing it into a concrete type, which is useful for routing the messages onward
+    /// as-is. Like [`parse`](TwitterStream::parse), the error does not terminate the stream,
+    /// and the message is skipped instead if [`Builder::skip_invalid`] is set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use twitter_stream::TwitterStream;
+    ///
+    /// # futures::executor::block_on(async {
+    /// let body = hyper_pkg::Body::from("{\"id\":1}\r\n{\"id\":\r\n[]\r\n");
+    /// let mut stream = TwitterStream::new(body).validated();
+    ///
+    /// assert_eq!(&*stream.next().await.unwrap().unwrap(), "{\"id\":1}");
+    /// assert!(matches!(
+    ///     stream.next().await.unwrap(),
+    ///     Err(twitter_stream::Error::Json(_)),
+    /// ));
+    /// assert_eq!(&*stream.next().await.unwrap().unwrap(), "[]");
+    /// # });
+    /// ```
+    #[cfg(feature = "json")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+    pub fn validated(self) -> Validated<B> {
+        Validated::new(self)
+    }
+
+    /// Returns the trailers of the HTTP response, which are available after the stream has
+    /// ended.
+    ///
+    /// Returns `None` if the stream has not ended yet or the response has no trailers.
+    pub fn trailers(&self) -> Option<&HeaderMap> {
+        self.inner.trailers()
+    }
+
+    /// Returns a reference to the underlying response body.
+    pub fn get_ref(&self) -> &B {
+        #[cfg(feature = "gzip")]
+        return self.inner.get_ref().get_ref();
+        #[cfg(not(feature = "gzip"))]
+        return self.inner.get_ref();
+    }
+
+    /// Returns the number of keep-alive blank lines received so far.
+    ///
+    /// The Streaming API sends a blank line every 30 seconds when there is no message to send,
+    /// so this can be used to tell whether the connection is alive even when no message arrives.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use twitter_stream::TwitterStream;
+    ///
+    /// # futures::executor::block_on(async {
+    /// let body = hyper_pkg::Body::from("\r\n{}\r\n\r\n");
+    /// let mut stream = TwitterStream::new(body);
+    /// while stream.try_next().await.unwrap().is_some() {}
+    ///
+    /// assert_eq!(stream.keep_alive_count(), 2);
+    /// assert_eq!(stream.message_count(), 1);
+    /// # });
+    /// ```
+    pub fn keep_alive_count(&self) -> u64 {
+        self.keep_alive_count
+    }
+
+    /// Returns the number of messages (non-blank lines) yielded so far.
+    pub fn message_count(&self) -> u64 {
+        self.message_count
+    }
+
+    /// Returns the statistics of the stream, which is useful for monitoring the connection.
+    ///
+    /// Twitter recommends reconnecting periodically (e.g. every 24 hours), which can be done by
+    /// checking the [`age`](StreamStats::age) of the connection.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use twitter_stream::TwitterStream;
+    ///
+    /// # futures::executor::block_on(async {
+    /// let body = hyper_pkg::Body::from("\r\n{}\r\n\r\n");
+    /// let mut stream = TwitterStream::new(body);
+    /// while stream.try_next().await.unwrap().is_some() {}
+    ///
+    /// let stats = stream.stats();
+    /// assert_eq!(stats.bytes_read, 8);
+    /// assert_eq!(stats.message_count, 1);
+    /// assert_eq!(stats.keep_alive_count, 2);
+    /// # });
+    /// ```
+    pub fn stats(&self) -> StreamStats {
+        StreamStats {
+            age: self.connected_at.elapsed(),
+            bytes_read: self.inner.bytes_read(),
+            message_count: self.message_count,
+            keep_alive_count: self.keep_alive_count,
+        }
+    }
+
+    /// Copies the raw response body to `writer` as it is read, including the keep-alive
+    /// blank lines and the length prefixes of `delimited` messages.
+    ///
+    /// This is useful for archiving the stream or reproducing a parsing issue. If the response
+    /// is gzip-encoded, the decoded body is copied (requires the `gzip` feature).
+    ///
+    /// Writing to `writer` does not block the stream. The chunks are held in a buffer of up to
+    /// 1 MiB until `writer` accepts them, and if the buffer fills up or `writer` fails, the stream
+    /// yields an [`Error::Tee`] once and stops copying. The stream waits for `writer` to
+    /// finish writing before ending.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # async fn doc() -> std::io::Result<()> {
+    /// # let token = twitter_stream::Token::from_parts("", "", "", "");
+    /// let file = tokio::fs::File::create("stream.log").await?;
+    /// let stream = twitter_stream::Builder::new(token)
+    ///     .track("@Twitter")
+    ///     .listen()
+    ///     .await
+    ///     .unwrap()
+    ///     .tee(file);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn tee<W>(mut self, writer: W) -> Self
+    where
+        W: tokio::io::AsyncWrite + Send + Sync + 'static,
+    {
+        self.inner.tee(writer);
+        self
+    }
+
+    /// Reads the messages ahead in a background task, buffering up to `capacity` messages while
+    /// the consumer is busy.
+    ///
+    /// The Streaming API disconnects a client that reads too slowly (with a `Stall` disconnect
+    /// message). Reading ahead keeps the connection drained during short spikes of processing
+    /// time.
+    /// When the buffer is full, the task stops reading until a message is consumed. The task
+    /// ends when the stream ends or the returned [`Prefetch`] is dropped.
+    ///
+    /// Note that [`headers`](TwitterStream::headers) and the counters are not accessible
+    /// after calling this method.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero or if called outside of a Tokio runtime.
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn prefetch(self, capacity: usize) -> Prefetch<B::Error>
+    where
+        B: Send + 'static,
+        B::Error: Send,
+    {
+        Prefetch::spawn(self, capacity)
+    }
+
+    /// Sends the messages to `tx` until the stream ends or the receiver is dropped.
+    ///
+    /// This is useful to decouple the task reading the stream from the tasks processing
+    /// the messages. A dropped receiver is treated as a graceful close: the returned future
+    /// resolves to `Ok(())` and the connection is closed. The same happens when the returned
+    /// [`AbortHandle`] is used to stop the future, which is useful when the future is spawned
+    /// and the receiver is shared with other tasks. If the stream yields an error, the future
+    /// resolves to the error without sending it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use twitter_stream::TwitterStream;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let body = hyper_pkg::Body::from("{\"id\":1}\r\n{\"id\":2}\r\n{\"id\":3}\r\n");
+    /// let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+    /// let (forward, abort) = TwitterStream::new(body).forward_to(tx);
+    /// let task = tokio::spawn(forward);
+    ///
+    /// // Stop after the first message.
+    /// assert_eq!(&*rx.recv().await.unwrap(), "{\"id\":1}");
+    /// abort.abort();
+    /// task.await.unwrap().unwrap();
+    /// # }
+    /// ```
+    #[cfg(feature = "tokio")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+    pub fn forward_to(
+        self,
+        tx: tokio::sync::mpsc::Sender<string::String<Bytes>>,
+    ) -> (
+        impl Future<Output = Result<(), Error<B::Error>>>,
+        AbortHandle,
+    ) {
+        let (abort, registration) = AbortHandle::new_pair();
+        let forward = Abortable::new(self.forward(tx), registration);
+        (async move { forward.await.unwrap_or(Ok(())) }, abort)
+    }
+
+    #[cfg(feature = "tokio")]
+    async fn forward(
+        self,
+        tx: tokio::sync::mpsc::Sender<string::String<Bytes>>,
+    ) -> Result<(), Error<B::Error>> {
+        let mut stream = Box::pin(self);
+        let closed = tx.closed();
+        tokio::pin!(closed);
+        loop {
+            let next = prefetch::Next {
+                stream: &mut stream,
+                closed: closed.as_mut(),
+            };
+            let message = match next.await {
+                Some(message) => message?,
+                None => return Ok(()),
+            };
+            if tx.send(message).await.is_err() {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Stops reading the stream and closes the connection, returning a future resolving to
+    /// the messages drained from the stream.
+    ///
+    /// The future reads the messages that are available without waiting for more data from
+    /// the network, i.e. the complete lines already buffered by the stream and the chunks that
+    /// the underlying body has already received, up to `max_messages` messages. It then waits
+    /// for the writer set with [`tee`](TwitterStream::tee) to write and flush the data copied
+    /// so far, and finally drops the response body.
+    ///
+    /// The future resolves to the drained messages along with the result of closing the stream.
+    /// If an error occurs, the future stops draining and resolves to the messages read before
+    /// the error and the error.
+    ///
+    /// With hyper, dropping the response body closes the underlying connection, which
+    /// the Streaming API treats as a normal disconnection. This means you can reconnect right
+    /// away without being disconnected as a duplicate stream.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use twitter_stream::TwitterStream;
+    ///
+    /// # futures::executor::block_on(async {
+    /// let body = hyper_pkg::Body::from("{\"id\":1}\r\n\r\n{\"id\":2}\r\n");
+    /// let (remaining, result) = TwitterStream::new(body).close(100).await;
+    /// result.unwrap();
+    /// let remaining: Vec<&str> = remaining.iter().map(|s| &**s).collect();
+    /// assert_eq!(remaining, ["{\"id\":1}", "{\"id\":2}"]);
+    /// # });
+    /// ```
+    #[cfg_attr(not(feature = "tokio"), allow(broken_intra_doc_links))]
+    pub fn close(self, max_messages: usize) -> Close<B> {
+        Close::new(self, max_messages)
+    }
+
+    /// Writes the data pending for the writer set with `tee` and flushes it.
+    fn poll_flush_tee(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Result<(), Error<B::Error>>> {
+        #[cfg(feature = "tokio")]
+        return self.project().inner.poll_flush_tee(cx).map_err(Error::Tee);
+        #[cfg(not(feature = "tokio"))]
+        {
+            let _ = (self, cx);
+            Poll::Ready(Ok(()))
+        }
+    }
+
     /// Creates a `Builder` for `TwitterStream`.
     pub fn builder<'a, C, A>(token: Token<C, A>) -> Builder<'a, Token<C, A>>
     where
@@ -155,7 +935,7 @@ impl<B: Body> TwitterStream<B> {
     }
 }
 
-#[cfg(feature = "hyper")]
+#[cfg(any(feature = "tls-native", feature = "tls-rustls"))]
 impl crate::hyper::TwitterStream {
     /// Connect to the filter stream, yielding Tweets from the users specified by `follow` argument.
     ///
@@ -227,6 +1007,51 @@ impl crate::hyper::TwitterStream {
     }
 }
 
+#[cfg(feature = "tokio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tokio")))]
+impl<R: tokio::io::AsyncBufRead> TwitterStream<ReaderBody<R>> {
+    /// Creates a `TwitterStream` reading the messages from `reader` instead of an HTTP response.
+    ///
+    /// The messages are delimited by CRLFs or LFs, and blank lines are skipped as keep-alive
+    /// signals. This is useful for replaying a stream recorded with
+    /// [`tee`](TwitterStream::tee), or a file of newline-delimited JSON, e.g. in tests.
+    /// To read from a file, wrap it with `tokio::io::BufReader`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use twitter_stream::TwitterStream;
+    ///
+    /// # futures::executor::block_on(async {
+    /// let dump: &[u8] = b"{\"id\":1}\r\n\r\n{\"id\":2}\n";
+    /// let messages: Vec<String> = TwitterStream::from_reader(dump)
+    ///     .map_ok(|json| json.to_string())
+    ///     .try_collect()
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(messages, ["{\"id\":1}", "{\"id\":2}"]);
+    /// # });
+    /// ```
+    pub fn from_reader(reader: R) -> Self {
+        let mut stream = TwitterStream::new(ReaderBody::new(reader));
+        stream.inner = stream.inner.lf(true);
+        stream
+    }
+}
+
+impl<T> From<Token<T, T>> for Auth<T> {
+    fn from(token: Token<T, T>) -> Self {
+        Auth::OAuth1(token)
+    }
+}
+
+impl<T> From<BearerToken<T>> for Auth<T> {
+    fn from(BearerToken(token): BearerToken<T>) -> Self {
+        Auth::Bearer(token)
+    }
+}
+
 impl<F, B, E> Future for FutureTwitterStream<F>
 where
     F: Future<Output = Result<Response<B>, E>>,
@@ -235,15 +1060,152 @@ where
     type Output = Result<TwitterStream<B>, Error<E>>;
 
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
-        let res = ready!(self.project().response.poll(cx).map_err(Error::Service)?);
+        let mut this = self.project();
+
+        if let Some(erroring) = this.erroring.as_mut().as_pin_mut() {
+            let e = ready!(erroring.poll_error(cx));
+            this.erroring.set(None);
+            return Poll::Ready(Err(e));
+        }
+
+        let response = if let Some(response) = this.response.as_mut().as_pin_mut() {
+            response
+        } else {
+            let e = this
+                .invalid_parameter
+                .take()
+                .expect("polled after completion");
+            return Poll::Ready(Err(Error::InvalidParameter(e)));
+        };
+
+        let res = match response.poll(cx) {
+            Poll::Ready(res) => res.map_err(Error::Service)?,
+            Poll::Pending => {
+                #[cfg(feature = "tokio")]
+                if let Some(duration) = this.config.connect_timeout {
+                    let sleep = this
+                        .connect_timeout
+                        .get_or_insert_with(|| Box::pin(tokio::time::sleep(duration)));
+                    if sleep.as_mut().poll(cx).is_ready() {
+                        this.response.set(None);
+                        return Poll::Ready(Err(Error::TimedOut));
+                    }
+                }
+                return Poll::Pending;
+            }
+        };
+
+        let (parts, body) = res.into_parts();
+
+        let backoff = if error::is_rate_limited_status(parts.status) {
+            // Start from 1 minute and double it on each attempt, as Twitter recommends.
+            let attempts = this
+                .config
+                .rate_limit_counter
+                .as_ref()
+                .map_or(0, |counter| counter.increment());
+            Some(Duration::from_secs(60 << attempts.min(5)))
+        } else {
+            None
+        };
+
+        #[cfg(feature = "gzip")]
+        let body = service::GzipDecoder::from_headers(&parts.headers, body);
+
+        if parts.status != StatusCode::OK {
+            // Read the body, which may describe the error.
+            this.erroring.set(Some(ErroringResponse {
+                body,
+                status: parts.status,
+                backoff,
+                retry_after: error::retry_after(&parts.headers),
+                buf: Vec::new(),
+            }));
+            let erroring = this.erroring.as_mut().as_pin_mut().unwrap();
+            let e = ready!(erroring.poll_error(cx));
+            this.erroring.set(None);
+            return Poll::Ready(Err(e));
+        }
+
+        #[cfg(feature = "gzip")]
+        if this.config.require_gzip && !body.is_encoded() {
+            this.response.set(None);
+            return Poll::Ready(Err(Error::Gzip(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "the response is not gzip-encoded",
+            ))));
+        }
+
+        if let Some(ref counter) = this.config.rate_limit_counter {
+            counter.reset();
+        }
+
+        let inner = Lines::with_capacity(body, this.config.line_buffer_capacity)
+            .delimited(this.config.delimited)
+            .max_length(this.config.max_line_length);
+        #[cfg(feature = "tokio")]
+        let inner = inner.idle_timeout(this.config.idle_timeout);
+
+        Poll::Ready(Ok(TwitterStream {
+            inner,
+            headers: parts.headers,
+            config: this.config.clone(),
+            connected_at: Instant::now(),
+            keep_alive_count: 0,
+            message_count: 0,
+        }))
+    }
+}
 
-        if res.status() != StatusCode::OK {
-            return Poll::Ready(Err(Error::Http(res.status())));
+impl<B: Body> ErroringResponse<B> {
+    /// The maximum length of an error response body to read.
+    const MAX_LEN: usize = 64 * 1024;
+
+    fn poll_error<E>(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Error<E>> {
+        let mut this = self.project();
+
+        while this.buf.len() < Self::MAX_LEN {
+            match ready!(this.body.as_mut().poll_data(cx)) {
+                Some(Ok(mut data)) => {
+                    while data.has_remaining() {
+                        let chunk = data.chunk();
+                        this.buf.extend_from_slice(chunk);
+                        let n = chunk.len();
+                        data.advance(n);
+                    }
+                }
+                // The body is only informative, so an error while reading it is not fatal.
+                Some(Err(_)) | None => break,
+            }
         }
+        this.buf.truncate(Self::MAX_LEN);
 
-        let inner = Lines::new(res.into_body());
+        if let Some(backoff) = *this.backoff {
+            return Poll::Ready(Error::RateLimited {
+                backoff,
+                retry_after: *this.retry_after,
+                body: mem::take(this.buf).into(),
+            });
+        }
+
+        #[cfg(feature = "json")]
+        if let Some(errors) = error::ApiError::from_body(this.buf) {
+            return Poll::Ready(Error::Twitter {
+                status: *this.status,
+                errors,
+                retry_after: *this.retry_after,
+            });
+        }
 
-        Poll::Ready(Ok(TwitterStream { inner }))
+        if this.buf.is_empty() && this.retry_after.is_none() {
+            Poll::Ready(Error::Http(*this.status))
+        } else {
+            Poll::Ready(Error::HttpResponse {
+                status: *this.status,
+                body: mem::take(this.buf).into(),
+                retry_after: *this.retry_after,
+            })
+        }
     }
 }
 
@@ -257,16 +1219,25 @@ where
         let mut this = self.project();
 
         loop {
-            let line = match ready!(this.inner.as_mut().poll_next(cx)?) {
+            let poll = this.inner.as_mut().poll_next(cx);
+            #[cfg(feature = "gzip")]
+            let poll = poll.map_err(Error::flatten);
+            let line = match ready!(poll?) {
                 Some(t) => t,
                 None => return std::task::Poll::Ready(None),
             };
 
             if line.iter().all(|&c| is_json_whitespace(c)) {
+                *this.keep_alive_count += 1;
                 continue;
             }
 
-            str::from_utf8(&line).map_err(Error::Utf8)?;
+            if let Err(e) = str::from_utf8(&line) {
+                if this.config.skip_invalid {
+                    continue;
+                }
+                return Poll::Ready(Some(Err(Error::Utf8(e))));
+            }
             let line = unsafe {
                 // Safety:
                 // - We have checked above that `line` is valid as UTF-8.
@@ -274,6 +1245,22 @@ where
                 // (https://github.com/carllerche/string/pull/17)
                 string::String::<Bytes>::from_utf8_unchecked(line)
             };
+
+            #[cfg(feature = "json")]
+            if let Some(ref on_warning) = this.config.on_warning {
+                if let Some(warning) = error::Warning::from_json(&line) {
+                    on_warning.call(warning);
+                }
+            }
+
+            #[cfg(feature = "json")]
+            if this.config.surface_disconnect {
+                if let Some(disconnect) = error::Disconnect::from_json(&line) {
+                    return Poll::Ready(Some(Err(Error::Disconnect(disconnect))));
+                }
+            }
+
+            *this.message_count += 1;
             return Poll::Ready(Some(Ok(line)));
         }
     }
@@ -283,3 +1270,197 @@ fn is_json_whitespace(c: u8) -> bool {
     // RFC7159 §2
     b" \t\n\r".contains(&c)
 }
+
+mod private {
+    use std::future::Future;
+
+    use http::Response;
+
+    /// A `Future` resolving to an HTTP response, used to name the type of the response body.
+    pub trait ResponseFuture {
+        type Body;
+    }
+
+    impl<F, B, E> ResponseFuture for F
+    where
+        F: Future<Output = Result<Response<B>, E>>,
+    {
+        type Body = B;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::{block_on, block_on_stream};
+
+    use super::*;
+    use crate::service::{MockBody, MockError, MockService};
+
+    const BODY: &str = "\r\n{\"id\":1}\r\n\r\n{\"id\":2}\r\n{\"id\":3}\r\n";
+
+    fn connect(body: MockBody) -> TwitterStream<MockBody> {
+        let service = MockService::new();
+        service.push(body);
+        block_on(Builder::new(BearerToken("AAAA")).listen_with_client(service)).unwrap()
+    }
+
+    #[test]
+    fn keep_alive_count() {
+        let body = hyper_pkg::Body::from("\r\n\r\n{\"id\":1}\r\n \r\n{\"id\":2}\r\n\r\n");
+        let mut stream = block_on_stream(TwitterStream::new(body));
+
+        assert_eq!(&*stream.next().unwrap().unwrap(), "{\"id\":1}");
+        assert_eq!(stream.keep_alive_count(), 2);
+        assert_eq!(stream.message_count(), 1);
+
+        // A line of whitespace is a keep-alive line as well.
+        assert_eq!(&*stream.next().unwrap().unwrap(), "{\"id\":2}");
+        assert_eq!(stream.keep_alive_count(), 3);
+        assert_eq!(stream.message_count(), 2);
+
+        assert!(stream.next().is_none());
+        assert_eq!(stream.keep_alive_count(), 4);
+        assert_eq!(stream.message_count(), 2);
+
+        // The lines read before an error are counted.
+        let body = MockBody::split_at(BODY, &[12]).fail_after(1);
+        let mut stream = block_on_stream(connect(body));
+        assert_eq!(&*stream.next().unwrap().unwrap(), "{\"id\":1}");
+        assert!(matches!(
+            stream.next(),
+            Some(Err(Error::Service(MockError)))
+        ));
+        assert_eq!(stream.keep_alive_count(), 1);
+        assert_eq!(stream.message_count(), 1);
+    }
+
+    #[test]
+    fn stats() {
+        for chunk_size in 1..=BODY.len() {
+            let mut stream = block_on_stream(connect(MockBody::split(BODY, chunk_size)));
+
+            assert_eq!(&*stream.next().unwrap().unwrap(), "{\"id\":1}");
+            let stats = stream.stats();
+            assert_eq!(stats.message_count, 1);
+            assert_eq!(stats.keep_alive_count, 1);
+            assert!(stats.bytes_read >= 12, "chunk size {}", chunk_size);
+
+            while stream.next().transpose().unwrap().is_some() {}
+            let stats = stream.stats();
+            assert_eq!(stats.message_count, 3);
+            assert_eq!(stats.keep_alive_count, 2);
+            assert_eq!(stats.bytes_read, BODY.len() as u64);
+        }
+    }
+
+    #[test]
+    fn stats_error() {
+        let body = MockBody::split_at(BODY, &[12, 16]).fail_after(2);
+        let mut stream = block_on_stream(connect(body));
+
+        assert_eq!(&*stream.next().unwrap().unwrap(), "{\"id\":1}");
+        assert!(matches!(
+            stream.next(),
+            Some(Err(Error::Service(MockError)))
+        ));
+        let stats = stream.stats();
+        assert_eq!(stats.message_count, 1);
+        assert_eq!(stats.bytes_read, 16);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn tee() {
+        use std::io;
+        use std::sync::{Arc, Mutex};
+
+        use futures::TryStreamExt;
+
+        #[derive(Clone, Default)]
+        struct Writer(Arc<Mutex<Vec<u8>>>);
+
+        impl tokio::io::AsyncWrite for Writer {
+            fn poll_write(
+                self: Pin<&mut Self>,
+                _: &mut Context<'_>,
+                buf: &[u8],
+            ) -> Poll<io::Result<usize>> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Poll::Ready(Ok(buf.len()))
+            }
+
+            fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        for &chunk_size in &[1, 5, BODY.len()] {
+            let writer = Writer::default();
+            let stream = connect(MockBody::split(BODY, chunk_size)).tee(writer.clone());
+            let messages: Vec<String> = stream
+                .map_ok(|s| s.to_string())
+                .try_collect()
+                .await
+                .unwrap();
+            assert_eq!(messages, ["{\"id\":1}", "{\"id\":2}", "{\"id\":3}"]);
+            // The keep-alive lines are copied as well.
+            assert_eq!(*writer.0.lock().unwrap(), BODY.as_bytes());
+        }
+
+        // The data read before an error is copied.
+        let writer = Writer::default();
+        let body = MockBody::split_at(BODY, &[12]).fail_after(1);
+        let mut stream = connect(body).tee(writer.clone());
+        assert_eq!(&*stream.try_next().await.unwrap().unwrap(), "{\"id\":1}");
+        assert!(matches!(
+            stream.try_next().await,
+            Err(Error::Service(MockError))
+        ));
+        let (_, result) = stream.close(0).await;
+        result.unwrap();
+        assert_eq!(*writer.0.lock().unwrap(), &BODY.as_bytes()[..12]);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn forward_to() {
+        let (tx, mut rx) = tokio::sync::mpsc::channel(1);
+        let (forward, _) = connect(MockBody::split(BODY, 4)).forward_to(tx);
+        let task = tokio::spawn(forward);
+        let mut messages = Vec::new();
+        while let Some(message) = rx.recv().await {
+            messages.push(message.to_string());
+        }
+        task.await.unwrap().unwrap();
+        assert_eq!(messages, ["{\"id\":1}", "{\"id\":2}", "{\"id\":3}"]);
+
+        // The error is returned instead of being sent.
+        let (tx, mut rx) = tokio::sync::mpsc::channel(4);
+        let body = MockBody::split_at(BODY, &[12]).fail_after(1);
+        let (forward, _) = connect(body).forward_to(tx);
+        assert!(matches!(forward.await, Err(Error::Service(MockError))));
+        assert_eq!(&*rx.recv().await.unwrap(), "{\"id\":1}");
+        assert!(rx.recv().await.is_none());
+
+        // A dropped receiver ends the future gracefully.
+        let (tx, rx) = tokio::sync::mpsc::channel(1);
+        drop(rx);
+        let (forward, _) = connect(MockBody::split(BODY, 4)).forward_to(tx);
+        forward.await.unwrap();
+
+        // So does the abort handle, even if the body would never end.
+        let (tx, _rx) = tokio::sync::mpsc::channel(1);
+        let (mut body_tx, body) = hyper_pkg::Body::channel();
+        body_tx
+            .try_send_data(Bytes::from_static(b"{\"id\":1}\r\n"))
+            .unwrap();
+        let (forward, abort) = TwitterStream::new(body).forward_to(tx);
+        let task = tokio::spawn(forward);
+        abort.abort();
+        task.await.unwrap().unwrap();
+    }
+}
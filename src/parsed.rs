@@ -0,0 +1,469 @@
+use std::collections::{HashSet, VecDeque};
+use std::fmt::{self, Formatter};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use futures_core::{ready, Stream};
+use http_body::Body;
+use pin_project_lite::pin_project;
+use serde::de::{self, DeserializeOwned, Deserializer, IgnoredAny, MapAccess, Visitor};
+
+use crate::{Error, TwitterStream};
+
+pin_project! {
+    /// A stream returned by [`TwitterStream::parse`], yielding the messages from the Streaming API
+    /// deserialized as `T`.
+    pub struct ParsedStream<B, T> {
+        #[pin]
+        inner: TwitterStream<B>,
+        marker: PhantomData<fn() -> T>,
+    }
+}
+
+pin_project! {
+    /// A stream returned by [`TwitterStream::with_raw`], yielding the messages from the Streaming
+    /// API deserialized as `T` along with the raw JSON strings.
+    pub struct WithRaw<B, T> {
+        #[pin]
+        inner: TwitterStream<B>,
+        marker: PhantomData<fn() -> T>,
+    }
+}
+
+pin_project! {
+    /// A stream returned by [`TwitterStream::tweets`], yielding only the messages from
+    /// the Streaming API that deserialize as `T`.
+    pub struct Tweets<B, T> {
+        #[pin]
+        inner: TwitterStream<B>,
+        marker: PhantomData<fn() -> T>,
+    }
+}
+
+pin_project! {
+    /// A stream returned by [`TwitterStream::validated`], yielding the messages from
+    /// the Streaming API that are valid JSON.
+    pub struct Validated<B> {
+        #[pin]
+        inner: TwitterStream<B>,
+    }
+}
+
+pin_project! {
+    /// A stream returned by [`TwitterStream::track_limit`], yielding the messages from
+    /// the Streaming API along with the number of undelivered Tweets reported by each limit
+    /// notice since the previous one.
+    pub struct TrackLimit<B> {
+        #[pin]
+        inner: TwitterStream<B>,
+        // The `track` value of the last limit notice.
+        track: u64,
+    }
+}
+
+pin_project! {
+    /// A stream returned by [`TwitterStream::dedup`], yielding the messages from the Streaming API
+    /// except for the Tweets whose IDs have been seen recently.
+    pub struct Dedup<B> {
+        #[pin]
+        inner: TwitterStream<B>,
+        // The remembered IDs in the order they were seen, the oldest first.
+        ids: VecDeque<u64>,
+        // The same IDs as `ids` for lookup.
+        seen: HashSet<u64>,
+        capacity: usize,
+    }
+}
+
+impl<B, T> ParsedStream<B, T> {
+    pub(crate) fn new(inner: TwitterStream<B>) -> Self {
+        ParsedStream {
+            inner,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the underlying `TwitterStream`.
+    pub fn get_ref(&self) -> &TwitterStream<B> {
+        &self.inner
+    }
+
+    /// Consumes the `ParsedStream`, returning the underlying `TwitterStream`.
+    pub fn into_inner(self) -> TwitterStream<B> {
+        self.inner
+    }
+}
+
+impl<B, T> Stream for ParsedStream<B, T>
+where
+    B: Body,
+    T: DeserializeOwned,
+{
+    type Item = Result<T, Error<B::Error>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        loop {
+            let line = match ready!(this.inner.as_mut().poll_next(cx)?) {
+                Some(line) => line,
+                None => return Poll::Ready(None),
+            };
+            match serde_json::from_str(&line) {
+                Ok(message) => return Poll::Ready(Some(Ok(message))),
+                Err(_) if this.inner.config.skip_invalid => continue,
+                Err(e) => return Poll::Ready(Some(Err(Error::Json(e)))),
+            }
+        }
+    }
+}
+
+impl<B, T> WithRaw<B, T> {
+    pub(crate) fn new(inner: TwitterStream<B>) -> Self {
+        WithRaw {
+            inner,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the underlying `TwitterStream`.
+    pub fn get_ref(&self) -> &TwitterStream<B> {
+        &self.inner
+    }
+
+    /// Consumes the `WithRaw`, returning the underlying `TwitterStream`.
+    pub fn into_inner(self) -> TwitterStream<B> {
+        self.inner
+    }
+}
+
+impl<B, T> Stream for WithRaw<B, T>
+where
+    B: Body,
+    T: DeserializeOwned,
+{
+    #[allow(clippy::type_complexity)]
+    type Item = Result<(string::String<Bytes>, Result<T, serde_json::Error>), Error<B::Error>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let line = match ready!(self.project().inner.poll_next(cx)?) {
+            Some(line) => line,
+            None => return Poll::Ready(None),
+        };
+        let message = serde_json::from_str(&line);
+        Poll::Ready(Some(Ok((line, message))))
+    }
+}
+
+impl<B, T> Tweets<B, T> {
+    pub(crate) fn new(inner: TwitterStream<B>) -> Self {
+        Tweets {
+            inner,
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns a reference to the underlying `TwitterStream`.
+    pub fn get_ref(&self) -> &TwitterStream<B> {
+        &self.inner
+    }
+
+    /// Consumes the `Tweets`, returning the underlying `TwitterStream`.
+    pub fn into_inner(self) -> TwitterStream<B> {
+        self.inner
+    }
+}
+
+impl<B, T> Stream for Tweets<B, T>
+where
+    B: Body,
+    T: DeserializeOwned,
+{
+    type Item = Result<T, Error<B::Error>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        loop {
+            let line = match ready!(this.inner.as_mut().poll_next(cx)?) {
+                Some(line) => line,
+                None => return Poll::Ready(None),
+            };
+            if let Ok(tweet) = serde_json::from_str(&line) {
+                return Poll::Ready(Some(Ok(tweet)));
+            }
+        }
+    }
+}
+
+impl<B> Validated<B> {
+    pub(crate) fn new(inner: TwitterStream<B>) -> Self {
+        Validated { inner }
+    }
+
+    /// Returns a reference to the underlying `TwitterStream`.
+    pub fn get_ref(&self) -> &TwitterStream<B> {
+        &self.inner
+    }
+
+    /// Consumes the `Validated`, returning the underlying `TwitterStream`.
+    pub fn into_inner(self) -> TwitterStream<B> {
+        self.inner
+    }
+}
+
+impl<B: Body> Stream for Validated<B> {
+    type Item = Result<string::String<Bytes>, Error<B::Error>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        loop {
+            let line = match ready!(this.inner.as_mut().poll_next(cx)?) {
+                Some(line) => line,
+                None => return Poll::Ready(None),
+            };
+            match serde_json::from_str::<IgnoredAny>(&line) {
+                Ok(_) => return Poll::Ready(Some(Ok(line))),
+                Err(_) if this.inner.config.skip_invalid => continue,
+                Err(e) => return Poll::Ready(Some(Err(Error::Json(e)))),
+            }
+        }
+    }
+}
+
+impl<B> TrackLimit<B> {
+    pub(crate) fn new(inner: TwitterStream<B>) -> Self {
+        TrackLimit { inner, track: 0 }
+    }
+
+    /// Returns the total number of undelivered Tweets reported so far.
+    pub fn total(&self) -> u64 {
+        self.track
+    }
+
+    /// Returns a reference to the underlying `TwitterStream`.
+    pub fn get_ref(&self) -> &TwitterStream<B> {
+        &self.inner
+    }
+
+    /// Consumes the `TrackLimit`, returning the underlying `TwitterStream`.
+    pub fn into_inner(self) -> TwitterStream<B> {
+        self.inner
+    }
+}
+
+impl<B: Body> Stream for TrackLimit<B> {
+    type Item = Result<(string::String<Bytes>, Option<u64>), Error<B::Error>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.project();
+        let line = match ready!(this.inner.poll_next(cx)?) {
+            Some(line) => line,
+            None => return Poll::Ready(None),
+        };
+        let last = this.track;
+        let delta = limit_track(&line).map(|track| {
+            // The count is cumulative, but do not trust it to be monotonic.
+            let delta = track.saturating_sub(*last);
+            *last = track.max(*last);
+            delta
+        });
+        Poll::Ready(Some(Ok((line, delta))))
+    }
+}
+
+/// Parses a JSON string as a limit notice, returning its `track` value.
+fn limit_track(json: &str) -> Option<u64> {
+    #[derive(serde::Deserialize)]
+    struct Message {
+        limit: Limit,
+    }
+
+    #[derive(serde::Deserialize)]
+    struct Limit {
+        track: u64,
+    }
+
+    // Avoid deserializing Tweets, which cannot have a `"limit"` key.
+    memchr::memmem::find(json.as_bytes(), b"\"limit\"")?;
+    serde_json::from_str::<Message>(json)
+        .ok()
+        .map(|message| message.limit.track)
+}
+
+impl<B> Dedup<B> {
+    pub(crate) fn new(inner: TwitterStream<B>, capacity: usize) -> Self {
+        Dedup {
+            inner,
+            ids: VecDeque::with_capacity(capacity),
+            seen: HashSet::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Replaces the underlying `TwitterStream` with `inner`, keeping the remembered IDs, and
+    /// returns the old one.
+    ///
+    /// This is meant to be called with a new connection after a reconnect, so that the Tweets
+    /// redelivered by the new connection are suppressed.
+    pub fn replace_inner(&mut self, inner: TwitterStream<B>) -> TwitterStream<B> {
+        std::mem::replace(&mut self.inner, inner)
+    }
+
+    /// Returns a reference to the underlying `TwitterStream`.
+    pub fn get_ref(&self) -> &TwitterStream<B> {
+        &self.inner
+    }
+
+    /// Consumes the `Dedup`, returning the underlying `TwitterStream`.
+    pub fn into_inner(self) -> TwitterStream<B> {
+        self.inner
+    }
+}
+
+impl<B: Body> Stream for Dedup<B> {
+    type Item = Result<string::String<Bytes>, Error<B::Error>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut this = self.project();
+        loop {
+            let line = match ready!(this.inner.as_mut().poll_next(cx)?) {
+                Some(line) => line,
+                None => return Poll::Ready(None),
+            };
+            let id = match tweet_id(&line) {
+                Some(id) => id,
+                None => return Poll::Ready(Some(Ok(line))),
+            };
+            if this.seen.contains(&id) {
+                continue;
+            }
+            if *this.capacity > 0 {
+                if this.ids.len() == *this.capacity {
+                    let oldest = this.ids.pop_front().unwrap();
+                    this.seen.remove(&oldest);
+                }
+                this.ids.push_back(id);
+                this.seen.insert(id);
+            }
+            return Poll::Ready(Some(Ok(line)));
+        }
+    }
+}
+
+/// Reads the top-level `id` of a JSON object, returning `None` if there is no such key.
+///
+/// This stops reading the object as soon as it finds the key, which is the second one in
+/// a Tweet, so the rest of the Tweet is not even checked for syntax errors.
+fn tweet_id(json: &str) -> Option<u64> {
+    struct IsId(bool);
+
+    struct IsIdVisitor;
+
+    struct IdVisitor;
+
+    impl<'de> de::Deserialize<'de> for IsId {
+        fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+            d.deserialize_str(IsIdVisitor)
+        }
+    }
+
+    impl<'de> Visitor<'de> for IsIdVisitor {
+        type Value = IsId;
+
+        fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.write_str("a string")
+        }
+
+        fn visit_str<E>(self, v: &str) -> Result<IsId, E> {
+            Ok(IsId(v == "id"))
+        }
+    }
+
+    impl<'de> Visitor<'de> for IdVisitor {
+        type Value = Option<u64>;
+
+        fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.write_str("a map")
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Option<u64>, A::Error> {
+            while let Some(IsId(is_id)) = map.next_key()? {
+                if is_id {
+                    return map.next_value().map(Some);
+                }
+                map.next_value::<IgnoredAny>()?;
+            }
+            Ok(None)
+        }
+    }
+
+    // Avoid scanning the messages without the key, like limit notices.
+    memchr::memmem::find(json.as_bytes(), b"\"id\"")?;
+    serde_json::Deserializer::from_str(json)
+        .deserialize_map(IdVisitor)
+        .ok()
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+    use futures::TryStreamExt;
+
+    use super::*;
+
+    #[test]
+    fn dedup_mock() {
+        use crate::service::{MockBody, MockService};
+        use crate::{BearerToken, Builder};
+
+        let service = MockService::new();
+        service
+            .push(MockBody::split(
+                "{\"id\":1}\r\n{\"id\":2}\r\n{\"id\":1}\r\n",
+                5,
+            ))
+            .push(MockBody::split(
+                "{\"id\":2}\r\n{\"delete\":{}}\r\n{\"id\":3}\r\n",
+                7,
+            ));
+        let builder = Builder::new(BearerToken("AAAA"));
+        let connect = || block_on(builder.listen_with_client(service.clone())).unwrap();
+
+        let mut stream = connect().dedup(2);
+        let lines: Vec<String> =
+            block_on((&mut stream).map_ok(|s| s.to_string()).try_collect()).unwrap();
+        assert_eq!(lines, ["{\"id\":1}", "{\"id\":2}"]);
+
+        // Reconnected.
+        stream.replace_inner(connect());
+        let lines: Vec<String> = block_on(stream.map_ok(|s| s.to_string()).try_collect()).unwrap();
+        assert_eq!(lines, ["{\"delete\":{}}", "{\"id\":3}"]);
+        assert_eq!(service.remaining(), 0);
+    }
+
+    #[test]
+    fn track_limit_mock() {
+        use crate::service::{MockBody, MockError};
+
+        let data = "{\"limit\":{\"track\":10}}\r\n{\"id\":1}\r\n\
+                    {\"limit\":{\"track\":8}}\r\n{\"limit\":{\"track\":25}}\r\n";
+        for chunk_size in 1..=data.len() {
+            let mut stream = TwitterStream::new(MockBody::split(data, chunk_size)).track_limit();
+            let deltas: Vec<_> =
+                block_on((&mut stream).map_ok(|(_, delta)| delta).try_collect()).unwrap();
+            // A decreasing count is not trusted.
+            assert_eq!(deltas, [Some(10), None, Some(0), Some(15)]);
+            assert_eq!(stream.total(), 25);
+        }
+
+        let body = MockBody::split_at(data, &[24]).fail_after(1);
+        let mut stream = TwitterStream::new(body).track_limit();
+        assert_eq!(block_on(stream.try_next()).unwrap().unwrap().1, Some(10));
+        assert!(matches!(
+            block_on(stream.try_next()),
+            Err(Error::Service(MockError))
+        ));
+    }
+}
@@ -0,0 +1,121 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use futures_core::Stream;
+use http_body::Body;
+use tokio::sync::mpsc;
+
+use crate::{Error, TwitterStream};
+
+/// A stream returned by [`TwitterStream::prefetch`], yielding the messages read ahead by
+/// a background task.
+#[derive(Debug)]
+pub struct Prefetch<E> {
+    rx: mpsc::Receiver<Result<string::String<Bytes>, Error<E>>>,
+}
+
+/// A future resolving to the next item of a stream, or `None` if `closed` resolves first.
+pub(crate) struct Next<'a, S, C> {
+    pub stream: &'a mut S,
+    pub closed: Pin<&'a mut C>,
+}
+
+impl<E: Send + 'static> Prefetch<E> {
+    pub(crate) fn spawn<B>(stream: TwitterStream<B>, capacity: usize) -> Self
+    where
+        B: Body<Error = E> + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel(capacity);
+
+        tokio::spawn(async move {
+            let mut stream = Box::pin(stream);
+            let closed = tx.closed();
+            tokio::pin!(closed);
+            loop {
+                let next = Next {
+                    stream: &mut stream,
+                    closed: closed.as_mut(),
+                };
+                let item = match next.await {
+                    Some(item) => item,
+                    None => break,
+                };
+                // This waits for the `Prefetch` to consume a message if the buffer is full.
+                if tx.send(item).await.is_err() {
+                    break;
+                }
+            }
+        });
+
+        Prefetch { rx }
+    }
+}
+
+impl<E> Stream for Prefetch<E> {
+    type Item = Result<string::String<Bytes>, Error<E>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
+
+impl<S: Stream + Unpin, C: Future> Future for Next<'_, S, C> {
+    type Output = Option<S::Item>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        // Stop reading if the receiver is dropped.
+        if self.closed.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(None);
+        }
+        Pin::new(&mut *self.stream).poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::TryStreamExt;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn prefetch() {
+        let body = hyper_pkg::Body::from("{\"id\":1}\r\n\r\n{\"id\":2}\r\n{\"id\":3}\r\n");
+        let messages: Vec<String> = TwitterStream::new(body)
+            .prefetch(1)
+            .map_ok(|message| message.to_string())
+            .try_collect()
+            .await
+            .unwrap();
+
+        assert_eq!(messages, ["{\"id\":1}", "{\"id\":2}", "{\"id\":3}"]);
+    }
+
+    #[tokio::test]
+    async fn prefetch_mock() {
+        use crate::service::{MockBody, MockError};
+        use crate::Error;
+
+        let data = "{\"id\":1}\r\n\r\n{\"id\":2}\r\n{\"id\":3}\r\n";
+        for chunk_size in 1..=data.len() {
+            let body = MockBody::split(data, chunk_size);
+            let messages: Vec<String> = TwitterStream::new(body)
+                .prefetch(2)
+                .map_ok(|message| message.to_string())
+                .try_collect()
+                .await
+                .unwrap();
+            assert_eq!(messages, ["{\"id\":1}", "{\"id\":2}", "{\"id\":3}"]);
+        }
+
+        // The error from the body is passed through after the messages read before it.
+        let body = MockBody::split_at(data, &[10]).fail_after(1);
+        let mut stream = TwitterStream::new(body).prefetch(4);
+        assert_eq!(&*stream.try_next().await.unwrap().unwrap(), "{\"id\":1}");
+        assert!(matches!(
+            stream.try_next().await,
+            Err(Error::Service(MockError))
+        ));
+    }
+}
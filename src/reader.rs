@@ -0,0 +1,63 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use futures_core::ready;
+use http::HeaderMap;
+use http_body::Body;
+use pin_project_lite::pin_project;
+use tokio::io::AsyncBufRead;
+
+pin_project! {
+    /// A [`Body`] reading from an [`AsyncBufRead`], used by [`TwitterStream::from_reader`].
+    ///
+    /// [`TwitterStream::from_reader`]: crate::TwitterStream::from_reader
+    #[derive(Debug)]
+    pub struct ReaderBody<R> {
+        #[pin]
+        reader: R,
+    }
+}
+
+impl<R> ReaderBody<R> {
+    pub(crate) fn new(reader: R) -> Self {
+        ReaderBody { reader }
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Consumes the `ReaderBody`, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R: AsyncBufRead> Body for ReaderBody<R> {
+    type Data = Bytes;
+    type Error = io::Error;
+
+    fn poll_data(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, io::Error>>> {
+        let mut reader = self.project().reader;
+        let buf = ready!(reader.as_mut().poll_fill_buf(cx))?;
+        if buf.is_empty() {
+            return Poll::Ready(None);
+        }
+        let chunk = Bytes::copy_from_slice(buf);
+        reader.consume(chunk.len());
+        Poll::Ready(Some(Ok(chunk)))
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, io::Error>> {
+        Poll::Ready(Ok(None))
+    }
+}
@@ -0,0 +1,172 @@
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use futures_core::Stream;
+use http_body::Body;
+
+use crate::builder::{Authorize, Builder};
+use crate::service::HttpService;
+use crate::{Error, FutureTwitterStream};
+
+/// A stream of the messages from the Streaming API whose parameters can be changed while
+/// listening.
+///
+/// [`reconfigure`](Reconfigurable::reconfigure) closes the current connection and opens
+/// a new one with the updated [`Builder`], so the messages from both connections are yielded
+/// by the same stream. This is useful for bots that adjust their `track` terms dynamically.
+///
+/// The connection is opened on the first poll. A connection error is yielded as an item, after
+/// which the stream yields `None` until it is reconfigured. The same applies to the end of
+/// the connection.
+///
+/// # Example
+///
+/// ```no_run
+/// use futures::prelude::*;
+/// use twitter_stream::Reconfigurable;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// # let token = twitter_stream::Token::from_parts("", "", "", "");
+/// let client = hyper_pkg::Client::builder()
+///     .build::<_, hyper_pkg::Body>(hyper_tls::HttpsConnector::new());
+/// let mut builder = twitter_stream::Builder::new(token);
+/// builder.track("@Twitter");
+/// let mut stream = Reconfigurable::new(builder, client);
+///
+/// while let Some(json) = stream.next().await {
+///     let json = json.unwrap();
+///     if json.contains("#NewTopic") {
+///         stream.reconfigure(|builder| {
+///             builder.track("@Twitter,#NewTopic");
+///         });
+///     }
+/// }
+/// # }
+/// ```
+pub struct Reconfigurable<'a, T, S: HttpService<B>, B> {
+    builder: Builder<'a, T>,
+    client: S,
+    // `None` if a connection has to be opened on the next poll.
+    stream: Option<Pin<Box<FutureTwitterStream<S::Future>>>>,
+    marker: PhantomData<fn(B)>,
+}
+
+impl<'a, T, S, B> Reconfigurable<'a, T, S, B>
+where
+    T: Authorize,
+    S: HttpService<B>,
+    B: From<Vec<u8>>,
+{
+    /// Creates a `Reconfigurable` connecting to the Streaming API with `builder` and `client`.
+    ///
+    /// `client` must be able to handle the `https` scheme.
+    pub fn new(builder: Builder<'a, T>, client: S) -> Self {
+        Reconfigurable {
+            builder,
+            client,
+            stream: None,
+            marker: PhantomData,
+        }
+    }
+
+    /// Updates the parameters with `f`, closing the current connection.
+    ///
+    /// A new connection with the updated parameters is opened on the next poll.
+    pub fn reconfigure<F>(&mut self, f: F)
+    where
+        F: FnOnce(&mut Builder<'a, T>),
+    {
+        f(&mut self.builder);
+        self.stream = None;
+    }
+
+    /// Returns a reference to the `Builder` used for the connections.
+    pub fn builder(&self) -> &Builder<'a, T> {
+        &self.builder
+    }
+
+    /// Gets a reference to the underlying HTTP client.
+    pub fn get_ref(&self) -> &S {
+        &self.client
+    }
+
+    /// Unwraps this `Reconfigurable`, returning the `Builder` and the HTTP client.
+    pub fn into_inner(self) -> (Builder<'a, T>, S) {
+        (self.builder, self.client)
+    }
+}
+
+impl<'a, T, S, B> Stream for Reconfigurable<'a, T, S, B>
+where
+    T: Authorize,
+    S: HttpService<B>,
+    S::ResponseBody: Body<Error = S::Error>,
+    B: From<Vec<u8>>,
+{
+    type Item = Result<string::String<Bytes>, Error<S::Error>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let builder = &this.builder;
+        let client = &mut this.client;
+        let stream = this
+            .stream
+            .get_or_insert_with(|| Box::pin(builder.listen_with_client(client)));
+        stream.as_mut().poll_next(cx)
+    }
+}
+
+// The connection is boxed and the other fields are never pinned.
+impl<'a, T, S: HttpService<B>, B> Unpin for Reconfigurable<'a, T, S, B> {}
+
+impl<'a, T: Debug, S: HttpService<B> + Debug, B> Debug for Reconfigurable<'a, T, S, B> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reconfigurable")
+            .field("builder", &self.builder)
+            .field("client", &self.client)
+            .field("connected", &self.stream.is_some())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+    use futures::{future, StreamExt};
+    use http::Request;
+    use tower::service_fn;
+
+    use super::*;
+    use crate::Token;
+
+    #[test]
+    fn reconfigure() {
+        let client = service_fn(|req: Request<Vec<u8>>| {
+            let body = if req.body() == b"track=a" {
+                "{\"track\":\"a\"}\r\n{\"track\":\"a\"}\r\n"
+            } else {
+                "{\"track\":\"b\"}\r\n"
+            };
+            let res = http::Response::new(hyper_pkg::Body::from(body));
+            future::ok::<_, hyper_pkg::Error>(res)
+        });
+
+        let mut builder = Builder::new(Token::from_parts("", "", "", ""));
+        builder.track("a");
+        let mut stream = Reconfigurable::new(builder, client);
+        let next = |stream: &mut Reconfigurable<_, _, _>| {
+            block_on(stream.next()).map(|result| result.unwrap().to_string())
+        };
+
+        assert_eq!(next(&mut stream).unwrap(), "{\"track\":\"a\"}");
+        stream.reconfigure(|builder| {
+            builder.track("b");
+        });
+        assert_eq!(next(&mut stream).unwrap(), "{\"track\":\"b\"}");
+        assert_eq!(next(&mut stream), None);
+    }
+}
@@ -0,0 +1,171 @@
+//! An [`HttpService`](crate::service::HttpService) implementation and type aliases for use with
+//! [`reqwest`](reqwest_pkg) crate's HTTP client.
+
+use std::convert::TryFrom;
+use std::fmt::{self, Debug, Formatter};
+use std::future::Future;
+use std::mem;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use futures_core::Stream;
+use http::{HeaderMap, Request, Response};
+use tower_service::Service;
+
+/// A type alias of [`FutureTwitterStream`](crate::FutureTwitterStream) using reqwest's HTTP client.
+pub type FutureTwitterStream = crate::FutureTwitterStream<ResponseFuture>;
+/// A type alias of [`Error`](crate::error::Error)
+/// whose `Service` variant contains [`reqwest::Error`](reqwest_pkg::Error).
+pub type Error = crate::Error<reqwest_pkg::Error>;
+/// A type alias of [`TwitterStream`](crate::TwitterStream) using reqwest's HTTP client.
+pub type TwitterStream = crate::TwitterStream<Body>;
+
+/// A wrapper of [`reqwest::Client`](reqwest_pkg::Client) implementing
+/// [`HttpService`](crate::service::HttpService).
+///
+/// # Example
+///
+/// ```no_run
+/// # async fn doc() {
+/// # let token = twitter_stream::Token::from_parts("", "", "", "");
+/// let client = reqwest_pkg::Client::new();
+/// let stream = twitter_stream::Builder::new(token)
+///     .track("@Twitter")
+///     .listen_with_client(twitter_stream::reqwest::Client::from(client))
+///     .await
+///     .unwrap();
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Client {
+    inner: reqwest_pkg::Client,
+}
+
+/// A future resolving to a response from a [`Client`].
+pub struct ResponseFuture {
+    inner: Pin<Box<dyn Future<Output = reqwest_pkg::Result<Response<Body>>> + Send>>,
+}
+
+/// The body of a response from a [`Client`].
+pub struct Body {
+    stream: Pin<Box<dyn Stream<Item = reqwest_pkg::Result<Bytes>> + Send>>,
+}
+
+impl Client {
+    /// Creates a `Client` with the default configuration of `reqwest::Client`.
+    pub fn new() -> Self {
+        Client::default()
+    }
+
+    /// Gets a reference to the underlying `reqwest::Client`.
+    pub fn get_ref(&self) -> &reqwest_pkg::Client {
+        &self.inner
+    }
+
+    /// Unwraps this `Client`, returning the underlying `reqwest::Client`.
+    pub fn into_inner(self) -> reqwest_pkg::Client {
+        self.inner
+    }
+}
+
+impl From<reqwest_pkg::Client> for Client {
+    fn from(inner: reqwest_pkg::Client) -> Self {
+        Client { inner }
+    }
+}
+
+impl Service<Request<reqwest_pkg::Body>> for Client {
+    type Response = Response<Body>;
+    type Error = reqwest_pkg::Error;
+    type Future = ResponseFuture;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<reqwest_pkg::Body>) -> ResponseFuture {
+        let client = self.inner.clone();
+        let req = reqwest_pkg::Request::try_from(req);
+        ResponseFuture {
+            inner: Box::pin(async move {
+                let mut res = client.execute(req?).await?;
+
+                let status = res.status();
+                let version = res.version();
+                let headers = mem::replace(res.headers_mut(), HeaderMap::new());
+
+                let mut res = Response::new(Body::from(res));
+                *res.status_mut() = status;
+                *res.version_mut() = version;
+                *res.headers_mut() = headers;
+                Ok(res)
+            }),
+        }
+    }
+}
+
+impl Future for ResponseFuture {
+    type Output = reqwest_pkg::Result<Response<Body>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.inner.as_mut().poll(cx)
+    }
+}
+
+impl Debug for ResponseFuture {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResponseFuture").finish()
+    }
+}
+
+impl From<reqwest_pkg::Response> for Body {
+    fn from(res: reqwest_pkg::Response) -> Self {
+        Body {
+            stream: Box::pin(res.bytes_stream()),
+        }
+    }
+}
+
+impl http_body::Body for Body {
+    type Data = Bytes;
+    type Error = reqwest_pkg::Error;
+
+    fn poll_data(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, reqwest_pkg::Error>>> {
+        self.stream.as_mut().poll_next(cx)
+    }
+
+    fn poll_trailers(
+        self: Pin<&mut Self>,
+        _: &mut Context<'_>,
+    ) -> Poll<Result<Option<HeaderMap>, reqwest_pkg::Error>> {
+        Poll::Ready(Ok(None))
+    }
+}
+
+impl Debug for Body {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Body").finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+    use futures::TryStreamExt;
+
+    use super::*;
+
+    #[test]
+    fn body() {
+        let res = Response::new("{\"id\":1}\r\n\r\n{\"id\":2}\r\n");
+        let body = Body::from(reqwest_pkg::Response::from(res));
+
+        let stream = crate::TwitterStream::new(body);
+        let messages = stream.map_ok(|s| s.to_string()).try_collect::<Vec<_>>();
+        assert_eq!(block_on(messages).unwrap(), ["{\"id\":1}", "{\"id\":2}"]);
+    }
+}
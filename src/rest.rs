@@ -0,0 +1,101 @@
+//! Helpers for sending requests to the REST API endpoints.
+
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Buf;
+use futures_core::ready;
+use http::{Request, Response};
+use http_body::Body;
+use tower_service::Service;
+
+use crate::error::ApiError;
+use crate::service::HttpService;
+use crate::Error;
+
+/// A future resolving when the service is ready to accept a request.
+struct Ready<'a, S, B>(&'a mut S, PhantomData<fn(B)>);
+
+/// A future reading a body to the end.
+struct ReadBody<B> {
+    body: Pin<Box<B>>,
+    buf: Vec<u8>,
+}
+
+/// Sends `req` with `client` and reads the response body, which is deserialized as `T`.
+///
+/// A response with a non-successful status code is converted into an `Error`.
+pub async fn send<S, B, T>(client: &mut S, req: Request<Vec<u8>>) -> Result<T, Error<S::Error>>
+where
+    S: HttpService<B>,
+    S::ResponseBody: Body<Error = S::Error>,
+    B: From<Vec<u8>>,
+    T: serde::de::DeserializeOwned,
+{
+    Ready(&mut *client, PhantomData)
+        .await
+        .map_err(Error::Service)?;
+    let res: Response<_> = client
+        .call(req.map(Into::into))
+        .await
+        .map_err(Error::Service)?;
+    let (parts, body) = res.into_parts();
+    let body = ReadBody {
+        body: Box::pin(body),
+        buf: Vec::new(),
+    }
+    .await
+    .map_err(Error::Service)?;
+
+    if !parts.status.is_success() {
+        let status = parts.status;
+        let retry_after = crate::error::retry_after(&parts.headers);
+        if let Some(errors) = ApiError::from_body(&body) {
+            return Err(Error::Twitter {
+                status,
+                errors,
+                retry_after,
+            });
+        }
+        if body.is_empty() && retry_after.is_none() {
+            return Err(Error::Http(status));
+        }
+        return Err(Error::HttpResponse {
+            status,
+            body: body.into(),
+            retry_after,
+        });
+    }
+
+    serde_json::from_slice(&body).map_err(Error::Json)
+}
+
+impl<S, B> Future for Ready<'_, S, B>
+where
+    S: Service<Request<B>>,
+{
+    type Output = Result<(), S::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.0.poll_ready(cx)
+    }
+}
+
+impl<B: Body> Future for ReadBody<B> {
+    type Output = Result<Vec<u8>, B::Error>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = &mut *self;
+        while let Some(mut data) = ready!(this.body.as_mut().poll_data(cx)?) {
+            while data.has_remaining() {
+                let chunk = data.chunk();
+                this.buf.extend_from_slice(chunk);
+                let n = chunk.len();
+                data.advance(n);
+            }
+        }
+        Poll::Ready(Ok(std::mem::take(&mut this.buf)))
+    }
+}
@@ -0,0 +1,294 @@
+//! A client for managing the rules of the Twitter API v2 [filtered stream][1].
+//!
+//! The filtered stream delivers only the Tweets matching the rules registered with
+//! the [`POST /2/tweets/search/stream/rules`][2] endpoint, so you need to add rules before
+//! connecting to the stream with [`Builder::v2_filtered_stream`](crate::Builder::v2_filtered_stream).
+//!
+//! [1]: https://developer.twitter.com/en/docs/twitter-api/tweets/filtered-stream/introduction
+//! [2]: https://developer.twitter.com/en/docs/twitter-api/tweets/filtered-stream/api-reference/post-tweets-search-stream-rules
+//!
+//! # Example
+//!
+//! ```no_run
+//! use twitter_stream::rules::{Rule, RulesClient};
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let client = hyper_pkg::Client::builder()
+//!     .build::<_, hyper_pkg::Body>(hyper_tls::HttpsConnector::new());
+//! let mut rules = RulesClient::new("bearer_token", client);
+//!
+//! let added = rules
+//!     .add(&[Rule::new("cat has:images").tag("cats with images")])
+//!     .await
+//!     .unwrap();
+//! println!("created {} rule(s)", added.summary.created);
+//!
+//! for rule in rules.list().await.unwrap() {
+//!     println!("{}: {}", rule.id, rule.value);
+//! }
+//! # }
+//! ```
+
+use http::header::{HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use http::Request;
+use http_body::Body;
+
+use crate::service::HttpService;
+use crate::Error;
+
+const RULES: &str = "https://api.twitter.com/2/tweets/search/stream/rules";
+
+/// A client for the [`/2/tweets/search/stream/rules`][1] endpoint.
+///
+/// [1]: https://developer.twitter.com/en/docs/twitter-api/tweets/filtered-stream/api-reference
+#[derive(Clone, Debug)]
+pub struct RulesClient<S, T = String> {
+    bearer_token: T,
+    client: S,
+}
+
+/// A rule to be added to the filtered stream.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, serde::Serialize)]
+pub struct Rule {
+    /// The query of the rule, e.g. `"cat has:images"`.
+    pub value: String,
+    /// A label to identify the rule, which is included in the `matching_rules` of the Tweets.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+}
+
+/// A rule registered to the filtered stream.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, serde::Deserialize)]
+#[non_exhaustive]
+pub struct ActiveRule {
+    /// The ID of the rule.
+    pub id: String,
+    /// The query of the rule.
+    pub value: String,
+    /// The label of the rule.
+    #[serde(default)]
+    pub tag: Option<String>,
+}
+
+/// The result of [`RulesClient::add`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct AddedRules {
+    /// The rules that have been created.
+    pub rules: Vec<ActiveRule>,
+    /// The numbers of the rules processed.
+    pub summary: Summary,
+}
+
+/// The numbers of the rules processed by a request to the rules endpoint.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, serde::Deserialize)]
+#[non_exhaustive]
+pub struct Summary {
+    /// The number of the rules that have been created.
+    #[serde(default)]
+    pub created: u64,
+    /// The number of the rules that have not been created, e.g. because of duplication.
+    #[serde(default)]
+    pub not_created: u64,
+    /// The number of the valid rules.
+    #[serde(default)]
+    pub valid: u64,
+    /// The number of the invalid rules.
+    #[serde(default)]
+    pub invalid: u64,
+    /// The number of the rules that have been deleted.
+    #[serde(default)]
+    pub deleted: u64,
+    /// The number of the rules that have not been deleted, e.g. because of a nonexistent ID.
+    #[serde(default)]
+    pub not_deleted: u64,
+}
+
+#[derive(serde::Deserialize)]
+struct Response<D> {
+    #[serde(default = "Vec::new")]
+    data: Vec<D>,
+    #[serde(default)]
+    meta: Meta,
+}
+
+#[derive(Default, serde::Deserialize)]
+struct Meta {
+    #[serde(default)]
+    summary: Summary,
+}
+
+/// A placeholder for the absent `data` in responses to delete requests.
+#[derive(serde::Deserialize)]
+struct Never {}
+
+impl<S, T: AsRef<str>> RulesClient<S, T> {
+    /// Creates a `RulesClient` authorizing the requests with `bearer_token` and sending them
+    /// with `client`.
+    ///
+    /// `client` must be able to handle the `https` scheme.
+    pub fn new(bearer_token: T, client: S) -> Self {
+        RulesClient {
+            bearer_token,
+            client,
+        }
+    }
+
+    /// Returns the rules currently registered to the filtered stream.
+    pub async fn list<B>(&mut self) -> Result<Vec<ActiveRule>, Error<S::Error>>
+    where
+        S: HttpService<B>,
+        S::ResponseBody: Body<Error = S::Error>,
+        B: From<Vec<u8>>,
+    {
+        let req = Request::get(RULES)
+            .header(AUTHORIZATION, self.authorization())
+            .body(Vec::new())
+            .unwrap();
+        let res: Response<ActiveRule> = crate::rest::send(&mut self.client, req).await?;
+        Ok(res.data)
+    }
+
+    /// Adds `rules` to the filtered stream.
+    pub async fn add<B>(&mut self, rules: &[Rule]) -> Result<AddedRules, Error<S::Error>>
+    where
+        S: HttpService<B>,
+        S::ResponseBody: Body<Error = S::Error>,
+        B: From<Vec<u8>>,
+    {
+        #[derive(serde::Serialize)]
+        struct Add<'a> {
+            add: &'a [Rule],
+        }
+
+        let res: Response<ActiveRule> = self.post(&Add { add: rules }).await?;
+        Ok(AddedRules {
+            rules: res.data,
+            summary: res.meta.summary,
+        })
+    }
+
+    /// Deletes the rules with the given IDs from the filtered stream.
+    pub async fn delete<B>(&mut self, ids: &[&str]) -> Result<Summary, Error<S::Error>>
+    where
+        S: HttpService<B>,
+        S::ResponseBody: Body<Error = S::Error>,
+        B: From<Vec<u8>>,
+    {
+        #[derive(serde::Serialize)]
+        struct Delete<'a> {
+            delete: Ids<'a>,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Ids<'a> {
+            ids: &'a [&'a str],
+        }
+
+        let res: Response<Never> = self
+            .post(&Delete {
+                delete: Ids { ids },
+            })
+            .await?;
+        Ok(res.meta.summary)
+    }
+
+    /// Gets a reference to the underlying HTTP client.
+    pub fn get_ref(&self) -> &S {
+        &self.client
+    }
+
+    /// Unwraps this `RulesClient`, returning the underlying HTTP client.
+    pub fn into_inner(self) -> S {
+        self.client
+    }
+
+    async fn post<B, P, D>(&mut self, payload: &P) -> Result<Response<D>, Error<S::Error>>
+    where
+        S: HttpService<B>,
+        S::ResponseBody: Body<Error = S::Error>,
+        B: From<Vec<u8>>,
+        P: serde::Serialize,
+        D: serde::de::DeserializeOwned,
+    {
+        let req = Request::post(RULES)
+            .header(AUTHORIZATION, self.authorization())
+            .header(CONTENT_TYPE, HeaderValue::from_static("application/json"))
+            .body(serde_json::to_vec(payload).unwrap())
+            .unwrap();
+        crate::rest::send(&mut self.client, req).await
+    }
+
+    fn authorization(&self) -> String {
+        format!("Bearer {}", self.bearer_token.as_ref())
+    }
+}
+
+impl Rule {
+    /// Creates a rule with the given query without a tag.
+    pub fn new(value: impl Into<String>) -> Self {
+        Rule {
+            value: value.into(),
+            tag: None,
+        }
+    }
+
+    /// Sets the tag of the rule.
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tag = Some(tag.into());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+    use futures::future;
+    use tower::service_fn;
+
+    use super::*;
+
+    #[test]
+    fn add() {
+        let client = service_fn(|req: Request<Vec<u8>>| {
+            assert_eq!(req.method(), http::Method::POST);
+            assert_eq!(req.uri(), RULES);
+            assert_eq!(req.headers()[AUTHORIZATION], "Bearer AAAA");
+            assert_eq!(
+                req.body(),
+                br#"{"add":[{"value":"cat has:images","tag":"cats"},{"value":"dog"}]}"#,
+            );
+            let body = hyper_pkg::Body::from(
+                r#"{"data":[{"value":"cat has:images","tag":"cats","id":"1"},{"value":"dog","id":"2"}],"meta":{"sent":"2021-01-01T00:00:00.000Z","summary":{"created":2,"not_created":0,"valid":2,"invalid":0}}}"#,
+            );
+            let res = http::Response::builder().status(201).body(body).unwrap();
+            future::ok::<_, hyper_pkg::Error>(res)
+        });
+
+        let mut rules = RulesClient::new("AAAA", client);
+        let added =
+            block_on(rules.add(&[Rule::new("cat has:images").tag("cats"), Rule::new("dog")]))
+                .unwrap();
+        assert_eq!(added.summary.created, 2);
+        assert_eq!(added.rules[0].id, "1");
+        assert_eq!(added.rules[0].tag.as_deref(), Some("cats"));
+        assert_eq!(added.rules[1].tag, None);
+    }
+
+    #[test]
+    fn delete() {
+        let client = service_fn(|req: Request<Vec<u8>>| {
+            assert_eq!(req.body(), br#"{"delete":{"ids":["1","2"]}}"#);
+            let body = hyper_pkg::Body::from(
+                r#"{"meta":{"sent":"2021-01-01T00:00:00.000Z","summary":{"deleted":1,"not_deleted":1}},"errors":[{"value":"2","details":["rule_id_not_found"]}]}"#,
+            );
+            future::ok::<_, hyper_pkg::Error>(http::Response::new(body))
+        });
+
+        let mut rules = RulesClient::new("AAAA", client);
+        let summary = block_on(rules.delete(&["1", "2"])).unwrap();
+        assert_eq!(summary.deleted, 1);
+        assert_eq!(summary.not_deleted, 1);
+    }
+}
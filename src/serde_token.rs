@@ -0,0 +1,121 @@
+//! Serialization of [`Token`] as an object of
+//! `{"consumer_key", "consumer_secret", "access_key", "access_secret"}`.
+//!
+//! Use this module with `#[serde(with = "twitter_stream::serde_token")]` on a `Token` field,
+//! or call [`deserialize`] directly to read a `Token` from a credential file.
+//!
+//! # Example
+//!
+//! ```
+//! use twitter_stream::Token;
+//!
+//! #[derive(serde::Deserialize)]
+//! struct Config {
+//!     #[serde(with = "twitter_stream::serde_token")]
+//!     token: Token,
+//!     track: String,
+//! }
+//!
+//! let config: Config = serde_json::from_str(r#"{
+//!     "token": {
+//!         "consumer_key": "ck",
+//!         "consumer_secret": "cs",
+//!         "access_key": "ak",
+//!         "access_secret": "as"
+//!     },
+//!     "track": "@Twitter"
+//! }"#).unwrap();
+//! assert_eq!(config.token.client.identifier, "ck");
+//! assert_eq!(config.token.token.secret, "as");
+//!
+//! let file = r#"{"consumer_key":"ck","consumer_secret":"cs","access_key":"ak","access_secret":"as"}"#;
+//! let token: Token =
+//!     twitter_stream::serde_token::deserialize(&mut serde_json::Deserializer::from_str(file))
+//!         .unwrap();
+//! assert_eq!(token.client.secret, "cs");
+//! ```
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::Token;
+
+#[derive(Serialize)]
+struct Ser<'a> {
+    consumer_key: &'a str,
+    consumer_secret: &'a str,
+    access_key: &'a str,
+    access_secret: &'a str,
+}
+
+#[derive(Deserialize)]
+struct De<C, A> {
+    consumer_key: C,
+    consumer_secret: C,
+    access_key: A,
+    access_secret: A,
+}
+
+/// Serializes a `Token` as an object of
+/// `{"consumer_key", "consumer_secret", "access_key", "access_secret"}`.
+pub fn serialize<C, A, S>(token: &Token<C, A>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    C: AsRef<str>,
+    A: AsRef<str>,
+    S: Serializer,
+{
+    Ser {
+        consumer_key: token.client.identifier.as_ref(),
+        consumer_secret: token.client.secret.as_ref(),
+        access_key: token.token.identifier.as_ref(),
+        access_secret: token.token.secret.as_ref(),
+    }
+    .serialize(serializer)
+}
+
+/// Deserializes a `Token` from an object of
+/// `{"consumer_key", "consumer_secret", "access_key", "access_secret"}`.
+pub fn deserialize<'de, C, A, D>(deserializer: D) -> Result<Token<C, A>, D::Error>
+where
+    C: AsRef<str> + Deserialize<'de>,
+    A: AsRef<str> + Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    let De {
+        consumer_key,
+        consumer_secret,
+        access_key,
+        access_secret,
+    } = De::deserialize(deserializer)?;
+    Ok(Token::from_parts(
+        consumer_key,
+        consumer_secret,
+        access_key,
+        access_secret,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let token = Token::from_parts("ck", "cs", "ak", "as");
+        let json = serialize(&token, serde_json::value::Serializer).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "consumer_key": "ck",
+                "consumer_secret": "cs",
+                "access_key": "ak",
+                "access_secret": "as",
+            }),
+        );
+
+        let token: Token = deserialize(json).unwrap();
+        assert_eq!(token.client.identifier, "ck");
+        assert_eq!(token.client.secret, "cs");
+        assert_eq!(token.token.identifier, "ak");
+        assert_eq!(token.token.secret, "as");
+    }
+}
@@ -1,17 +1,25 @@
 //! A trait alias for [`Service`](tower_service::Service).
 
+#[cfg(feature = "gzip")]
+pub use self::gzip::GzipDecoder;
+#[cfg(any(test, feature = "test-util"))]
+pub use self::mock::{MockBody, MockError, MockService};
+pub use self::reconnect::{Reconnect, ReconnectFuture};
+
 use http::{Request, Response};
 use http_body::Body;
 use tower_service::Service;
 
 use private::Sealed;
 
-#[cfg_attr(not(feature = "hyper"), allow(intra_doc_link_resolution_failure))]
+#[cfg_attr(not(feature = "hyper-client"), allow(broken_intra_doc_links))]
 /// An HTTP client (like [`hyper::Client`](hyper_pkg::client::Client)).
 ///
 /// This is just an alias for [`tower_service::Service`](tower_service::Service)
 /// introduced to reduce the number of type parameters in `Builder::listen_with_client`.
-pub trait HttpService<B>: Service<Request<B>> + Sealed<B> {
+pub trait HttpService<B>:
+    Service<Request<B>, Response = Response<Self::ResponseBody>> + Sealed<B>
+{
     /// Body of the responses given by the service.
     type ResponseBody: Body;
 }
@@ -24,6 +32,633 @@ where
     type ResponseBody = ResB;
 }
 
+#[cfg(feature = "gzip")]
+mod gzip {
+    use std::io::Write;
+    use std::mem;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use bytes::{Buf, Bytes};
+    use flate2::write::GzDecoder;
+    use futures_core::ready;
+    use http::header::{HeaderMap, CONTENT_ENCODING};
+    use http_body::Body;
+    use pin_project_lite::pin_project;
+
+    use crate::error::Error;
+
+    pin_project! {
+        /// A [`Body`] that decodes a gzip-encoded response body.
+        ///
+        /// [`FutureTwitterStream`](crate::FutureTwitterStream) wraps the response body with
+        /// this when the response has a `content-encoding: gzip` header, so gzip works with any
+        /// [`HttpService`](super::HttpService).
+        #[cfg_attr(docsrs, doc(cfg(feature = "gzip")))]
+        pub struct GzipDecoder<B> {
+            #[pin]
+            body: B,
+            // `None` if the body is not encoded.
+            decoder: Option<GzDecoder<Vec<u8>>>,
+            body_done: bool,
+        }
+    }
+
+    impl<B: Body> GzipDecoder<B> {
+        /// Wraps a gzip-encoded `body`.
+        pub fn new(body: B) -> Self {
+            GzipDecoder {
+                body,
+                decoder: Some(GzDecoder::new(Vec::new())),
+                body_done: false,
+            }
+        }
+
+        /// Wraps a `body` that is decoded only if `headers` has a `content-encoding: gzip`
+        /// header. Otherwise, the body is passed through as-is.
+        pub fn from_headers(headers: &HeaderMap, body: B) -> Self {
+            let gzip = headers
+                .get(CONTENT_ENCODING)
+                .map_or(false, |v| v.as_bytes().eq_ignore_ascii_case(b"gzip"));
+            if gzip {
+                GzipDecoder::new(body)
+            } else {
+                GzipDecoder {
+                    body,
+                    decoder: None,
+                    body_done: false,
+                }
+            }
+        }
+
+        /// Returns whether the body is decoded, i.e. whether it is gzip-encoded.
+        pub(crate) fn is_encoded(&self) -> bool {
+            self.decoder.is_some()
+        }
+
+        /// Returns a reference to the underlying body.
+        pub fn get_ref(&self) -> &B {
+            &self.body
+        }
+
+        /// Unwraps this `GzipDecoder`, returning the underlying body.
+        pub fn into_inner(self) -> B {
+            self.body
+        }
+    }
+
+    impl<B: Body> Body for GzipDecoder<B> {
+        type Data = Bytes;
+        type Error = Error<B::Error>;
+
+        fn poll_data(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<Option<Result<Bytes, Self::Error>>> {
+            let mut this = self.project();
+
+            loop {
+                if *this.body_done {
+                    return Poll::Ready(None);
+                }
+
+                let mut data = match ready!(this.body.as_mut().poll_data(cx)) {
+                    Some(Ok(data)) => data,
+                    Some(Err(e)) => return Poll::Ready(Some(Err(Error::Service(e)))),
+                    None => {
+                        *this.body_done = true;
+                        let decoder = match *this.decoder {
+                            Some(ref mut decoder) => decoder,
+                            None => return Poll::Ready(None),
+                        };
+                        // Checks the trailing CRC and size of the gzip stream.
+                        if let Err(e) = decoder.try_finish() {
+                            return Poll::Ready(Some(Err(Error::Gzip(e))));
+                        }
+                        let rest = mem::take(decoder.get_mut());
+                        return Poll::Ready(if rest.is_empty() {
+                            None
+                        } else {
+                            Some(Ok(rest.into()))
+                        });
+                    }
+                };
+
+                let decoder = match *this.decoder {
+                    Some(ref mut decoder) => decoder,
+                    None => return Poll::Ready(Some(Ok(data.copy_to_bytes(data.remaining())))),
+                };
+
+                while data.has_remaining() {
+                    let chunk = data.chunk();
+                    let n = chunk.len();
+                    if let Err(e) = decoder.write_all(chunk) {
+                        *this.body_done = true;
+                        return Poll::Ready(Some(Err(Error::Gzip(e))));
+                    }
+                    data.advance(n);
+                }
+
+                let decoded = mem::take(decoder.get_mut());
+                if !decoded.is_empty() {
+                    return Poll::Ready(Some(Ok(decoded.into())));
+                }
+            }
+        }
+
+        fn poll_trailers(
+            self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+        ) -> Poll<Result<Option<HeaderMap>, Self::Error>> {
+            self.project()
+                .body
+                .poll_trailers(cx)
+                .map_err(Error::Service)
+        }
+
+        fn is_end_stream(&self) -> bool {
+            self.body_done || (self.decoder.is_none() && self.body.is_end_stream())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::convert::Infallible;
+        use std::io::Write;
+
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+        use futures::executor::block_on;
+        use futures::{future, TryStreamExt};
+        use http::header::CONTENT_ENCODING;
+        use http::Response;
+
+        use crate::{Config, Error, FutureTwitterStream};
+
+        fn listen(body: Vec<u8>) -> Result<Vec<String>, Error<hyper_pkg::Error>> {
+            let res = Response::builder()
+                .header(CONTENT_ENCODING, "gzip")
+                .body(hyper_pkg::Body::from(body))
+                .unwrap();
+            let stream = block_on(connect(res, Config::default())).unwrap();
+            block_on(stream.map_ok(|s| s.to_string()).try_collect())
+        }
+
+        fn connect(
+            res: Response<hyper_pkg::Body>,
+            config: Config,
+        ) -> FutureTwitterStream<future::Ready<Result<Response<hyper_pkg::Body>, Infallible>>>
+        {
+            FutureTwitterStream {
+                response: Some(future::ok(res)),
+                invalid_parameter: None,
+                erroring: None,
+                connect_timeout: None,
+                stream: None,
+                config,
+            }
+        }
+
+        #[test]
+        fn decode() {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder
+                .write_all(b"{\"id\":1}\r\n\r\n{\"id\":2}\r\n")
+                .unwrap();
+            let body = encoder.finish().unwrap();
+
+            assert_eq!(listen(body.clone()).unwrap(), ["{\"id\":1}", "{\"id\":2}"]);
+
+            // Corrupt the CRC in the trailer.
+            let mut corrupt = body;
+            let crc = corrupt.len() - 8;
+            corrupt[crc] ^= 0xFF;
+            assert!(matches!(listen(corrupt), Err(Error::Gzip(_))));
+        }
+
+        #[test]
+        fn require_gzip() {
+            let config = Config {
+                require_gzip: true,
+                ..Config::default()
+            };
+
+            let res = Response::new(hyper_pkg::Body::from("{}\r\n"));
+            let result = block_on(connect(res, config.clone()));
+            assert!(matches!(result, Err(Error::Gzip(_))));
+
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(b"{}\r\n").unwrap();
+            let res = Response::builder()
+                .header(CONTENT_ENCODING, "gzip")
+                .body(hyper_pkg::Body::from(encoder.finish().unwrap()))
+                .unwrap();
+            assert!(block_on(connect(res, config)).is_ok());
+        }
+    }
+}
+
+mod reconnect {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use futures_core::ready;
+    use http::Request;
+    use pin_project_lite::pin_project;
+    use tower_service::Service;
+
+    /// A [`Service`] wrapper which re-issues a request if the inner service fails.
+    ///
+    /// Only the errors of the inner service (e.g. a connection failure) are retried, and an HTTP
+    /// error status is passed through as-is. The request is re-issued immediately, so you may want
+    /// to combine this with a rate limiting layer.
+    ///
+    /// The request body must be `Clone`. `hyper`'s `Client` can be used with
+    /// an [`http_body::Full`] body for this purpose.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use twitter_stream::service::Reconnect;
+    ///
+    /// # let token = twitter_stream::Token::from_parts("", "", "", "");
+    /// let conn = hyper_tls::HttpsConnector::new();
+    /// let client = hyper_pkg::Client::builder().build::<_, http_body::Full<bytes::Bytes>>(conn);
+    ///
+    /// let stream = twitter_stream::Builder::new(token)
+    ///     .track("@Twitter")
+    ///     .listen_with_client(Reconnect::new(client, 3));
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct Reconnect<S> {
+        inner: S,
+        max_retries: usize,
+    }
+
+    pin_project! {
+        /// The future returned by [`Reconnect`].
+        pub struct ReconnectFuture<S: Service<Request<B>>, B> {
+            // `None` while waiting for `service` to be ready.
+            #[pin]
+            future: Option<S::Future>,
+            service: S,
+            request: Request<B>,
+            retries: usize,
+        }
+    }
+
+    impl<S> Reconnect<S> {
+        /// Wraps `inner` to re-issue a request up to `max_retries` times.
+        pub fn new(inner: S, max_retries: usize) -> Self {
+            Reconnect { inner, max_retries }
+        }
+
+        /// Returns a reference to the inner service.
+        pub fn get_ref(&self) -> &S {
+            &self.inner
+        }
+
+        /// Consumes the `Reconnect`, returning the inner service.
+        pub fn into_inner(self) -> S {
+            self.inner
+        }
+    }
+
+    impl<S, B> Service<Request<B>> for Reconnect<S>
+    where
+        S: Service<Request<B>> + Clone,
+        B: Clone,
+    {
+        type Response = S::Response;
+        type Error = S::Error;
+        type Future = ReconnectFuture<S, B>;
+
+        fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), S::Error>> {
+            self.inner.poll_ready(cx)
+        }
+
+        fn call(&mut self, request: Request<B>) -> Self::Future {
+            ReconnectFuture {
+                future: Some(self.inner.call(clone_request(&request))),
+                service: self.inner.clone(),
+                request,
+                retries: self.max_retries,
+            }
+        }
+    }
+
+    impl<S, B> Future for ReconnectFuture<S, B>
+    where
+        S: Service<Request<B>>,
+        B: Clone,
+    {
+        type Output = Result<S::Response, S::Error>;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let mut this = self.project();
+
+            loop {
+                if let Some(future) = this.future.as_mut().as_pin_mut() {
+                    match ready!(future.poll(cx)) {
+                        Ok(res) => return Poll::Ready(Ok(res)),
+                        Err(e) if *this.retries == 0 => return Poll::Ready(Err(e)),
+                        Err(_) => {
+                            *this.retries -= 1;
+                            this.future.set(None);
+                        }
+                    }
+                }
+
+                ready!(this.service.poll_ready(cx))?;
+                let future = this.service.call(clone_request(this.request));
+                this.future.set(Some(future));
+            }
+        }
+    }
+
+    fn clone_request<B: Clone>(request: &Request<B>) -> Request<B> {
+        let mut clone = Request::new(request.body().clone());
+        *clone.method_mut() = request.method().clone();
+        *clone.uri_mut() = request.uri().clone();
+        *clone.version_mut() = request.version();
+        *clone.headers_mut() = request.headers().clone();
+        clone
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        use futures::executor::block_on;
+        use futures::future;
+        use http::Response;
+        use tower::{service_fn, ServiceExt};
+
+        use super::*;
+
+        #[test]
+        fn reconnect() {
+            let calls = Arc::new(AtomicUsize::new(0));
+            let service = {
+                let calls = calls.clone();
+                service_fn(move |req: Request<Vec<u8>>| {
+                    assert_eq!(req.body(), b"track=%40Twitter");
+                    let ok = calls.fetch_add(1, Ordering::SeqCst) == 2;
+                    future::ready(if ok { Ok(Response::new(())) } else { Err(()) })
+                })
+            };
+            let req = || Request::new(b"track=%40Twitter".to_vec());
+
+            let res = block_on(Reconnect::new(service.clone(), 2).oneshot(req()));
+            assert!(res.is_ok());
+            assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+            calls.store(0, Ordering::SeqCst);
+            let res = block_on(Reconnect::new(service, 1).oneshot(req()));
+            assert!(res.is_err());
+            assert_eq!(calls.load(Ordering::SeqCst), 2);
+        }
+    }
+}
+
+#[cfg(any(test, feature = "test-util"))]
+mod mock {
+    use std::collections::VecDeque;
+    use std::error;
+    use std::fmt::{self, Display, Formatter};
+    use std::future::{self, Ready};
+    use std::pin::Pin;
+    use std::sync::{Arc, Mutex, MutexGuard};
+    use std::task::{Context, Poll};
+
+    use bytes::Bytes;
+    use http::{HeaderMap, Request, Response, StatusCode};
+    use http_body::Body;
+    use tower_service::Service;
+
+    /// An [`HttpService`](super::HttpService) replaying prepared responses, for testing code
+    /// that consumes a [`TwitterStream`](crate::TwitterStream).
+    ///
+    /// Each call to the service takes the response that was pushed first. The service fails
+    /// with a [`MockError`] when it has no response left. The clones of a `MockService` share
+    /// the same responses.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use futures::prelude::*;
+    /// use twitter_stream::service::{MockBody, MockService};
+    ///
+    /// # futures::executor::block_on(async {
+    /// let service = MockService::new();
+    /// service.push(MockBody::split("{\"id\":1}\r\n{\"id\":2}\r\n", 3));
+    ///
+    /// # let token = twitter_stream::Token::from_parts("", "", "", "");
+    /// let stream = twitter_stream::Builder::new(token)
+    ///     .track("@Twitter")
+    ///     .listen_with_client(service)
+    ///     .await
+    ///     .unwrap();
+    /// let messages: Vec<_> = stream.map_ok(|s| s.to_string()).try_collect().await.unwrap();
+    /// assert_eq!(messages, ["{\"id\":1}", "{\"id\":2}"]);
+    /// # });
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "test-util")))]
+    #[derive(Clone, Debug, Default)]
+    pub struct MockService {
+        responses: Arc<Mutex<Responses>>,
+    }
+
+    type Responses = VecDeque<Result<Response<MockBody>, MockError>>;
+
+    /// A response body yielding prepared chunks, used with [`MockService`].
+    #[cfg_attr(docsrs, doc(cfg(feature = "test-util")))]
+    #[derive(Clone, Debug, Default)]
+    pub struct MockBody {
+        chunks: VecDeque<Bytes>,
+        // The number of chunks to yield before failing, if any.
+        fail_after: Option<usize>,
+    }
+
+    /// The error returned by [`MockService`] and [`MockBody`].
+    #[cfg_attr(docsrs, doc(cfg(feature = "test-util")))]
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    pub struct MockError;
+
+    impl MockService {
+        /// Creates a `MockService` without any response.
+        pub fn new() -> Self {
+            MockService::default()
+        }
+
+        /// Adds a `200 OK` response with `body`.
+        pub fn push(&self, body: MockBody) -> &Self {
+            self.push_response(Response::new(body))
+        }
+
+        /// Adds a response.
+        pub fn push_response(&self, response: Response<MockBody>) -> &Self {
+            self.lock().push_back(Ok(response));
+            self
+        }
+
+        /// Adds a response with `status` and `body`, e.g. to simulate a refused connection.
+        pub fn push_status(&self, status: StatusCode, body: MockBody) -> &Self {
+            let mut response = Response::new(body);
+            *response.status_mut() = status;
+            self.push_response(response)
+        }
+
+        /// Adds a connection failure, with which the service fails with a [`MockError`].
+        pub fn push_error(&self) -> &Self {
+            self.lock().push_back(Err(MockError));
+            self
+        }
+
+        /// Returns the number of the responses that have not been used.
+        pub fn remaining(&self) -> usize {
+            self.lock().len()
+        }
+
+        fn lock(&self) -> MutexGuard<'_, Responses> {
+            self.responses.lock().unwrap_or_else(|e| e.into_inner())
+        }
+    }
+
+    impl Service<Request<Vec<u8>>> for MockService {
+        type Response = Response<MockBody>;
+        type Error = MockError;
+        type Future = Ready<Result<Response<MockBody>, MockError>>;
+
+        fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), MockError>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _: Request<Vec<u8>>) -> Self::Future {
+            future::ready(self.lock().pop_front().unwrap_or(Err(MockError)))
+        }
+    }
+
+    impl MockBody {
+        /// Creates a `MockBody` yielding `chunks`.
+        pub fn new<I>(chunks: I) -> Self
+        where
+            I: IntoIterator,
+            I::Item: Into<Bytes>,
+        {
+            MockBody {
+                chunks: chunks.into_iter().map(Into::into).collect(),
+                fail_after: None,
+            }
+        }
+
+        /// Creates a `MockBody` yielding `data` in chunks of `chunk_size` bytes.
+        ///
+        /// The chunks may split a message, or even a CRLF, at any byte.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `chunk_size` is zero.
+        pub fn split(data: impl Into<Bytes>, chunk_size: usize) -> Self {
+            assert!(chunk_size != 0, "chunk size must be non-zero");
+            let mut data = data.into();
+            let mut chunks = VecDeque::with_capacity(data.len() / chunk_size + 1);
+            while !data.is_empty() {
+                chunks.push_back(data.split_to(chunk_size.min(data.len())));
+            }
+            MockBody {
+                chunks,
+                fail_after: None,
+            }
+        }
+
+        /// Creates a `MockBody` yielding `data` split at the given byte offsets.
+        ///
+        /// # Panics
+        ///
+        /// Panics if `offsets` is not in ascending order or an offset is out of bounds.
+        pub fn split_at(data: impl Into<Bytes>, offsets: &[usize]) -> Self {
+            let mut data = data.into();
+            let mut chunks = VecDeque::with_capacity(offsets.len() + 1);
+            let mut prev = 0;
+            for &offset in offsets {
+                assert!(offset >= prev, "offsets must be in ascending order");
+                chunks.push_back(data.split_to(offset - prev));
+                prev = offset;
+            }
+            chunks.push_back(data);
+            MockBody {
+                chunks,
+                fail_after: None,
+            }
+        }
+
+        /// Makes the body fail with a [`MockError`] after yielding `n` chunks, simulating
+        /// a dropped connection.
+        pub fn fail_after(mut self, n: usize) -> Self {
+            self.fail_after = Some(n);
+            self
+        }
+    }
+
+    impl Body for MockBody {
+        type Data = Bytes;
+        type Error = MockError;
+
+        fn poll_data(
+            mut self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+        ) -> Poll<Option<Result<Bytes, MockError>>> {
+            if let Some(ref mut n) = self.fail_after {
+                if *n == 0 {
+                    self.chunks.clear();
+                    self.fail_after = None;
+                    return Poll::Ready(Some(Err(MockError)));
+                }
+                *n -= 1;
+            }
+            Poll::Ready(self.chunks.pop_front().map(Ok))
+        }
+
+        fn poll_trailers(
+            self: Pin<&mut Self>,
+            _: &mut Context<'_>,
+        ) -> Poll<Result<Option<HeaderMap>, MockError>> {
+            Poll::Ready(Ok(None))
+        }
+    }
+
+    impl Display for MockError {
+        fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            f.write_str("mock error")
+        }
+    }
+
+    impl error::Error for MockError {}
+
+    #[cfg(test)]
+    mod tests {
+        use futures::executor::block_on_stream;
+
+        use super::*;
+        use crate::TwitterStream;
+
+        #[test]
+        fn fail_after() {
+            let body = MockBody::split_at("{\"id\":1}\r\n{\"id\":2}\r\n", &[9, 12]).fail_after(2);
+            let mut stream = block_on_stream(TwitterStream::new(body));
+
+            assert_eq!(&*stream.next().unwrap().unwrap(), "{\"id\":1}");
+            match stream.next().unwrap() {
+                Err(crate::Error::Service(MockError)) => {}
+                other => panic!("unexpected item: {:?}", other),
+            }
+        }
+    }
+}
+
 mod private {
     use http::{Request, Response};
     use http_body::Body;
@@ -0,0 +1,215 @@
+use std::fmt::{self, Debug, Formatter};
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Bytes;
+use futures_core::Stream;
+use http_body::Body;
+
+use crate::builder::{Authorize, Builder};
+use crate::service::HttpService;
+use crate::{Error, FutureTwitterStream};
+
+/// A set of connections to the Streaming API sharing one HTTP client, yielding the messages
+/// from all of them tagged with the index of the connection.
+///
+/// This is useful for following disjoint sets of users on several connections. Every connection
+/// is opened with the same `client`, so a `hyper::Client` shares its TLS connector and
+/// connection pool among them instead of creating a new one for each connection as
+/// [`Builder::listen`] does.
+///
+/// The messages are yielded in the order they arrive, polling the connections in turn so that
+/// a busy connection does not starve the others. A connection error is yielded as an item
+/// tagged with the index of the connection, after which the connection ends. The indices of
+/// the other connections are not affected by the end of a connection. The stream ends when all
+/// the connections have ended.
+///
+/// # Example
+///
+/// ```no_run
+/// use futures::prelude::*;
+/// use twitter_stream::StreamSet;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// # let token = twitter_stream::Token::from_parts("", "", "", "");
+/// let client = hyper_pkg::Client::builder()
+///     .build::<_, hyper_pkg::Body>(hyper_tls::HttpsConnector::new());
+/// let mut streams = StreamSet::new(client);
+/// let mut builder = twitter_stream::Builder::new(token);
+/// streams.push(builder.follow(&[783214][..]));
+/// streams.push(builder.follow(&[2244994945][..]));
+///
+/// while let Some((i, json)) = streams.next().await {
+///     println!("{}: {}", i, json.unwrap());
+/// }
+/// # }
+/// ```
+#[cfg_attr(
+    not(any(feature = "tls-native", feature = "tls-rustls")),
+    allow(broken_intra_doc_links)
+)]
+pub struct StreamSet<S: HttpService<B>, B> {
+    client: S,
+    // `None` if the connection has ended.
+    #[allow(clippy::type_complexity)]
+    streams: Vec<Option<Pin<Box<FutureTwitterStream<S::Future>>>>>,
+    // The index of the connection to be polled first on the next poll.
+    next: usize,
+    marker: PhantomData<fn(B)>,
+}
+
+impl<S, B> StreamSet<S, B>
+where
+    S: HttpService<B>,
+    B: From<Vec<u8>>,
+{
+    /// Creates an empty `StreamSet` opening the connections with `client`.
+    ///
+    /// `client` must be able to handle the `https` scheme.
+    pub fn new(client: S) -> Self {
+        StreamSet {
+            client,
+            streams: Vec::new(),
+            next: 0,
+            marker: PhantomData,
+        }
+    }
+
+    /// Opens a connection with `builder` and adds it to the set, returning the index with which
+    /// its messages are tagged.
+    ///
+    /// # Panics
+    ///
+    /// This will call `<S as Service>::call` without checking for `<S as Service>::poll_ready`
+    /// and may cause a panic if `client` is not ready to send an HTTP request yet.
+    pub fn push<T>(&mut self, builder: &Builder<'_, T>) -> usize
+    where
+        T: Authorize,
+        S::ResponseBody: Body<Error = S::Error>,
+    {
+        let stream = builder.listen_with_client(&mut self.client);
+        self.streams.push(Some(Box::pin(stream)));
+        self.streams.len() - 1
+    }
+
+    /// Returns the number of the connections added to the set, including the ones that have
+    /// ended.
+    pub fn len(&self) -> usize {
+        self.streams.len()
+    }
+
+    /// Returns `true` if no connection has been added to the set.
+    pub fn is_empty(&self) -> bool {
+        self.streams.is_empty()
+    }
+
+    /// Gets a reference to the underlying HTTP client.
+    pub fn get_ref(&self) -> &S {
+        &self.client
+    }
+
+    /// Gets a mutable reference to the underlying HTTP client.
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.client
+    }
+}
+
+impl<S, B> Stream for StreamSet<S, B>
+where
+    S: HttpService<B>,
+    S::ResponseBody: Body<Error = S::Error>,
+{
+    type Item = (usize, Result<string::String<Bytes>, Error<S::Error>>);
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let len = this.streams.len();
+        let mut live = false;
+
+        for i in (this.next..len).chain(0..this.next) {
+            let stream = match this.streams[i] {
+                Some(ref mut stream) => stream,
+                None => continue,
+            };
+            match stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    this.next = (i + 1) % len;
+                    return Poll::Ready(Some((i, item)));
+                }
+                Poll::Ready(None) => this.streams[i] = None,
+                Poll::Pending => live = true,
+            }
+        }
+
+        if live {
+            Poll::Pending
+        } else {
+            Poll::Ready(None)
+        }
+    }
+}
+
+// The connections are boxed and the other fields are never pinned.
+impl<S: HttpService<B>, B> Unpin for StreamSet<S, B> {}
+
+impl<S: HttpService<B> + Debug, B> Debug for StreamSet<S, B> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let live = self.streams.iter().filter(|s| s.is_some()).count();
+        f.debug_struct("StreamSet")
+            .field("client", &self.client)
+            .field("len", &self.streams.len())
+            .field("live", &live)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+    use futures::{future, StreamExt};
+    use http::Request;
+    use tower::service_fn;
+
+    use super::*;
+    use crate::Token;
+
+    #[test]
+    fn merge() {
+        let client = service_fn(|req: Request<Vec<u8>>| {
+            let res = if req.body() == b"follow=1" {
+                http::Response::new(hyper_pkg::Body::from("{\"a\":1}\r\n{\"a\":2}\r\n"))
+            } else if req.body() == b"follow=2" {
+                http::Response::new(hyper_pkg::Body::from("{\"b\":1}\r\n"))
+            } else {
+                http::Response::builder()
+                    .status(401)
+                    .body(hyper_pkg::Body::empty())
+                    .unwrap()
+            };
+            future::ok::<_, hyper_pkg::Error>(res)
+        });
+
+        let mut streams = StreamSet::new(client);
+        let mut builder = Builder::new(Token::from_parts("", "", "", ""));
+        assert_eq!(streams.push(builder.follow(&[1][..])), 0);
+        assert_eq!(streams.push(builder.follow(&[2][..])), 1);
+        assert_eq!(streams.push(builder.follow(&[3][..])), 2);
+
+        let items = block_on(streams.collect::<Vec<_>>());
+        let items = items
+            .into_iter()
+            .map(|(i, result)| (i, result.map(|s| s.to_string()).map_err(|_| ())))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            items,
+            [
+                (0, Ok("{\"a\":1}".to_owned())),
+                (1, Ok("{\"b\":1}".to_owned())),
+                (2, Err(())),
+                (0, Ok("{\"a\":2}".to_owned())),
+            ],
+        );
+    }
+}
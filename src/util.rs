@@ -1,10 +1,19 @@
+#[cfg(feature = "tokio")]
+use std::collections::VecDeque;
 use std::fmt::{self, Display, Formatter};
-use std::mem;
+#[cfg(feature = "tokio")]
+use std::future::Future;
+#[cfg(feature = "tokio")]
+use std::io;
 use std::pin::Pin;
+use std::str;
 use std::task::{Context, Poll};
+#[cfg(feature = "tokio")]
+use std::time::Duration;
 
-use bytes::{Buf, Bytes};
+use bytes::{Buf, Bytes, BytesMut};
 use futures_core::{ready, Stream};
+use http::HeaderMap;
 use http_body::Body;
 use pin_project_lite::pin_project;
 
@@ -44,83 +53,416 @@ pin_project! {
         #[pin]
         body: B,
         body_done: bool,
+        // Whether the body has yielded all of its data, while the trailers may be pending.
+        data_done: bool,
+        trailers: Option<HeaderMap>,
+        // The total length of the chunks read from the body.
+        bytes_read: u64,
+        // The rest of the last chunk of the body.
         buf: Bytes,
+        // The incomplete line that has been read from the previous chunks.
+        partial: BytesMut,
+        // The initial capacity of `partial`.
+        capacity: usize,
+        // Whether the messages are prefixed by their lengths (`delimited=length`).
+        delimited: bool,
+        // Whether a bare LF also terminates a line.
+        lf: bool,
+        // The maximum length of a line, beyond which the stream fails.
+        max_length: Option<usize>,
+        // The length of the next message in `delimited` mode.
+        length: Option<usize>,
+        idle_timeout: Option<IdleTimeout>,
+        tap: Option<Tap>,
     }
 }
 
+#[cfg(feature = "tokio")]
+struct IdleTimeout {
+    duration: Duration,
+    sleep: Pin<Box<tokio::time::Sleep>>,
+}
+
+// `pin_project!` does not accept `#[cfg]` on fields, so `Lines` has the `idle_timeout` field
+// regardless of the feature.
+/// A writer receiving a copy of every chunk of the body.
+#[cfg(feature = "tokio")]
+struct Tap {
+    writer: Pin<Box<dyn tokio::io::AsyncWrite + Send + Sync>>,
+    // The chunks that have not been written to `writer` yet.
+    pending: VecDeque<Bytes>,
+    pending_len: usize,
+}
+
+#[cfg(not(feature = "tokio"))]
+enum IdleTimeout {}
+
+#[cfg(not(feature = "tokio"))]
+enum Tap {}
+
 impl<B: Body> Lines<B> {
     pub fn new(body: B) -> Self {
+        Lines::with_capacity(body, 0)
+    }
+
+    /// Creates a `Lines` whose buffer for an incomplete line is allocated with `capacity` bytes.
+    pub fn with_capacity(body: B, capacity: usize) -> Self {
         Lines {
             body,
             body_done: false,
+            data_done: false,
+            trailers: None,
+            bytes_read: 0,
             buf: Bytes::new(),
+            partial: BytesMut::with_capacity(capacity),
+            capacity,
+            delimited: false,
+            lf: false,
+            max_length: None,
+            length: None,
+            idle_timeout: None,
+            tap: None,
         }
     }
 
+    /// Makes the stream read the messages according to their length prefixes
+    /// (`delimited=length`) rather than looking for CRLFs.
+    pub fn delimited(mut self, delimited: bool) -> Self {
+        self.delimited = delimited;
+        self
+    }
+
+    /// Makes the stream fail with `Error::LineTooLong` if a line exceeds `max_length` bytes.
+    pub fn max_length(mut self, max_length: Option<usize>) -> Self {
+        self.max_length = max_length;
+        self
+    }
+
+    /// Makes the stream split the lines at LFs as well as CRLFs.
+    #[cfg(feature = "tokio")]
+    pub fn lf(mut self, lf: bool) -> Self {
+        self.lf = lf;
+        self
+    }
+
+    /// Makes the stream fail with `Error::TimedOut` if the body yields no data for `duration`.
+    #[cfg(feature = "tokio")]
+    pub fn idle_timeout(mut self, duration: Option<Duration>) -> Self {
+        self.idle_timeout = duration.map(IdleTimeout::new);
+        self
+    }
+
+    /// Makes the stream copy every chunk of the body to `writer`.
+    #[cfg(feature = "tokio")]
+    pub fn tee<W>(&mut self, writer: W)
+    where
+        W: tokio::io::AsyncWrite + Send + Sync + 'static,
+    {
+        self.tap = Some(Tap {
+            writer: Box::pin(writer),
+            pending: VecDeque::new(),
+            pending_len: 0,
+        });
+    }
+
+    /// Writes the chunks pending for the writer set with `tee` and flushes it.
+    #[cfg(feature = "tokio")]
+    pub fn poll_flush_tee(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match *self.project().tap {
+            Some(ref mut tap) => tap.poll_write(cx),
+            None => Poll::Ready(Ok(())),
+        }
+    }
+
+    /// Returns a reference to the underlying body.
+    pub fn get_ref(&self) -> &B {
+        &self.body
+    }
+
+    /// Returns the trailers of the body, which are available after the stream has ended.
+    pub fn trailers(&self) -> Option<&HeaderMap> {
+        self.trailers.as_ref()
+    }
+
+    /// Returns the total length of the chunks read from the body.
+    pub fn bytes_read(&self) -> u64 {
+        self.bytes_read
+    }
+
     #[allow(clippy::type_complexity)]
     fn poll_body(
         self: Pin<&mut Self>,
         cx: &mut Context<'_>,
-    ) -> Poll<Option<Result<B::Data, Error<B::Error>>>> {
-        let this = self.project();
+    ) -> Poll<Option<Result<Bytes, Error<B::Error>>>> {
+        let mut this = self.project();
+
+        #[cfg(feature = "tokio")]
+        if let Some(ref mut tap) = *this.tap {
+            let result = match tap.poll_write(cx) {
+                Poll::Ready(result) => result,
+                // Wait for the writer to catch up before ending the stream.
+                Poll::Pending if *this.body_done => return Poll::Pending,
+                Poll::Pending if tap.pending_len > Tap::CAPACITY => Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    "the writer is not keeping up with the stream",
+                )),
+                Poll::Pending => Ok(()),
+            };
+            if let Err(e) = result {
+                *this.tap = None;
+                return Poll::Ready(Some(Err(Error::Tee(e))));
+            }
+        }
+
         if *this.body_done {
-            Poll::Ready(None)
-        } else if let Some(result) = ready!(this.body.poll_data(cx)) {
-            Poll::Ready(Some(result.map_err(Error::Service)))
-        } else {
-            *this.body_done = true;
-            Poll::Ready(None)
+            return Poll::Ready(None);
         }
-    }
-}
 
-impl<B: Body> Stream for Lines<B> {
-    type Item = Result<Bytes, Error<B::Error>>;
+        if !*this.data_done {
+            let poll = this.body.as_mut().poll_data(cx);
 
-    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        if let Some(line) = remove_first_line(self.as_mut().project().buf) {
-            return Poll::Ready(Some(Ok(line)));
+            #[cfg(feature = "tokio")]
+            if let Some(ref mut timeout) = *this.idle_timeout {
+                if poll.is_ready() {
+                    timeout.reset();
+                } else if timeout.sleep.as_mut().poll(cx).is_ready() {
+                    *this.body_done = true;
+                    *this.buf = Bytes::new();
+                    this.partial.clear();
+                    return Poll::Ready(Some(Err(Error::TimedOut)));
+                }
+            }
+
+            if let Some(result) = ready!(poll) {
+                let mut data = result.map_err(Error::Service)?;
+                let data = data.copy_to_bytes(data.remaining());
+                *this.bytes_read += data.len() as u64;
+                #[cfg(feature = "tokio")]
+                if let Some(ref mut tap) = *this.tap {
+                    tap.push(data.clone());
+                }
+                return Poll::Ready(Some(Ok(data)));
+            }
+            *this.data_done = true;
         }
 
-        // Now `self.buf` does not have a CRLF.
-        // Extend the buffer until a CRLF is found.
+        // Read the trailers so that an error reported in them is not lost.
+        let trailers = ready!(this.body.poll_trailers(cx));
+        *this.body_done = true;
+        *this.trailers = trailers.map_err(Error::Service)?;
+        Poll::Ready(None)
+    }
+}
 
+impl<B: Body> Lines<B> {
+    #[allow(clippy::type_complexity)]
+    fn poll_line(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Bytes, Error<B::Error>>>> {
         loop {
-            let mut chunk = loop {
-                if let Some(c) = ready!(self.as_mut().poll_body(cx)?) {
-                    if c.has_remaining() {
-                        break c;
+            let this = self.as_mut().project();
+
+            if !this.buf.is_empty() {
+                if this.partial.last() == Some(&b'\r') && this.buf[0] == b'\n' {
+                    // Drop the CRLF split across the chunks.
+                    this.partial.truncate(this.partial.len() - 1);
+                    this.buf.advance(1);
+                    if exceeds(*this.max_length, this.partial.len()) {
+                        return Poll::Ready(Some(Err(self.line_too_long())));
                     }
-                } else if self.buf.is_empty() {
-                    return Poll::Ready(None);
-                } else {
-                    // `self.buf` does not have CRLF so it is safe to return its content as-is.
-                    let ret = mem::take(self.as_mut().project().buf);
-                    return Poll::Ready(Some(Ok(ret)));
+                    return Poll::Ready(Some(Ok(this.partial.split().freeze())));
                 }
-            };
 
+                if let Some(line) = remove_first_line(this.buf, *this.lf) {
+                    if exceeds(*this.max_length, this.partial.len() + line.len()) {
+                        return Poll::Ready(Some(Err(self.line_too_long())));
+                    }
+                    if this.partial.is_empty() {
+                        return Poll::Ready(Some(Ok(line)));
+                    }
+                    this.partial.extend_from_slice(&line);
+                    return Poll::Ready(Some(Ok(this.partial.split().freeze())));
+                }
+
+                // Now `self.buf` does not have a CRLF.
+                // Keep it in `self.partial` until a CRLF is found. A trailing CR may be the first
+                // half of a CRLF, which does not count towards the length of the line.
+                let cr = (this.buf.last() == Some(&b'\r')) as usize;
+                if exceeds(*this.max_length, this.partial.len() + this.buf.len() - cr) {
+                    return Poll::Ready(Some(Err(self.line_too_long())));
+                }
+                reserve(this.partial, this.buf.len(), *this.capacity);
+                this.partial.extend_from_slice(this.buf);
+                *this.buf = Bytes::new();
+            }
+
+            if let Some(chunk) = ready!(self.as_mut().poll_body(cx)?) {
+                *self.as_mut().project().buf = chunk;
+            } else if self.partial.is_empty() {
+                return Poll::Ready(None);
+            } else if exceeds(self.max_length, self.partial.len()) {
+                return Poll::Ready(Some(Err(self.line_too_long())));
+            } else {
+                // `self.partial` does not have CRLF so it is safe to return its content as-is.
+                let ret = self.as_mut().project().partial.split().freeze();
+                return Poll::Ready(Some(Ok(ret)));
+            }
+        }
+    }
+
+    /// Discards the buffered data and ends the stream because of a line exceeding `max_length`.
+    fn line_too_long(self: Pin<&mut Self>) -> Error<B::Error> {
+        let this = self.project();
+        *this.body_done = true;
+        *this.buf = Bytes::new();
+        *this.partial = BytesMut::new();
+        Error::LineTooLong
+    }
+
+    /// Reads exactly `n` bytes, or the rest of the body if it ends before that.
+    #[allow(clippy::type_complexity)]
+    fn poll_exact(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        n: usize,
+    ) -> Poll<Option<Result<Bytes, Error<B::Error>>>> {
+        loop {
             let this = self.as_mut().project();
 
-            if chunk.chunk()[0] == b'\n' && this.buf.last() == Some(&b'\r') {
-                // Drop the CRLF
-                this.buf.truncate(this.buf.len() - 1);
-                chunk.advance(1);
+            if this.partial.is_empty() && this.buf.len() >= n {
+                return Poll::Ready(Some(Ok(this.buf.split_to(n))));
+            }
 
-                let chunk = chunk.copy_to_bytes(chunk.remaining());
-                return Poll::Ready(Some(Ok(mem::replace(this.buf, chunk))));
+            let needed = n - this.partial.len();
+            if this.buf.len() >= needed {
+                this.partial.extend_from_slice(&this.buf.split_to(needed));
+                return Poll::Ready(Some(Ok(this.partial.split().freeze())));
             }
 
-            let mut chunk = chunk.copy_to_bytes(chunk.remaining());
+            reserve(this.partial, needed, *this.capacity);
+            this.partial.extend_from_slice(this.buf);
+            *this.buf = Bytes::new();
 
-            if let Some(line) = remove_first_line(&mut chunk) {
-                let ret = concat_bytes(this.buf, line);
-                *this.buf = chunk;
+            if let Some(chunk) = ready!(self.as_mut().poll_body(cx)?) {
+                *self.as_mut().project().buf = chunk;
+            } else if self.partial.is_empty() {
+                return Poll::Ready(None);
+            } else {
+                let ret = self.as_mut().project().partial.split().freeze();
                 return Poll::Ready(Some(Ok(ret)));
             }
+        }
+    }
+}
+
+impl<B: Body> Stream for Lines<B> {
+    type Item = Result<Bytes, Error<B::Error>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if !self.delimited {
+            return self.poll_line(cx);
+        }
+
+        let length = if let Some(length) = self.length {
+            length
+        } else {
+            let line = match ready!(self.as_mut().poll_line(cx)?) {
+                Some(line) => line,
+                None => return Poll::Ready(None),
+            };
+            match parse_length(&line) {
+                Some(length) if exceeds(self.max_length, length) => {
+                    return Poll::Ready(Some(Err(self.line_too_long())));
+                }
+                Some(length) => {
+                    *self.as_mut().project().length = Some(length);
+                    length
+                }
+                // Keep-alive blank lines are not prefixed by a length. Return them (and anything
+                // else that is not a length) as-is.
+                None => return Poll::Ready(Some(Ok(line))),
+            }
+        };
+
+        let mut message = match ready!(self.as_mut().poll_exact(cx, length)?) {
+            Some(message) => message,
+            None => return Poll::Ready(None),
+        };
+        *self.project().length = None;
 
-            *this.buf = concat_bytes(this.buf, chunk);
+        // The length includes the trailing CRLF.
+        if message.ends_with(b"\r\n") {
+            message.truncate(message.len() - 2);
         }
+
+        Poll::Ready(Some(Ok(message)))
+    }
+}
+
+fn parse_length(line: &[u8]) -> Option<usize> {
+    if line.is_empty() || !line.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    // The line consists only of ASCII digits.
+    str::from_utf8(line).ok()?.parse().ok()
+}
+
+#[cfg(feature = "tokio")]
+impl IdleTimeout {
+    fn new(duration: Duration) -> Self {
+        IdleTimeout {
+            duration,
+            sleep: Box::pin(tokio::time::sleep(duration)),
+        }
+    }
+
+    fn reset(&mut self) {
+        let deadline = tokio::time::Instant::now() + self.duration;
+        self.sleep.as_mut().reset(deadline);
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Tap {
+    /// The maximum number of bytes to hold for a writer that is not ready.
+    const CAPACITY: usize = 1024 * 1024;
+
+    fn push(&mut self, chunk: Bytes) {
+        if !chunk.is_empty() {
+            self.pending_len += chunk.len();
+            self.pending.push_back(chunk);
+        }
+    }
+
+    /// Writes the pending chunks to the writer.
+    fn poll_write(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while let Some(chunk) = self.pending.front_mut() {
+            let n = ready!(self.writer.as_mut().poll_write(cx, chunk))?;
+            if n == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            chunk.advance(n);
+            self.pending_len -= n;
+            if chunk.is_empty() {
+                self.pending.pop_front();
+            }
+        }
+
+        self.writer.as_mut().poll_flush(cx)
+    }
+}
+
+/// A future resolving to the next item of a stream.
+#[cfg(feature = "tokio")]
+pub struct Next<'a, S>(pub &'a mut S);
+
+#[cfg(feature = "tokio")]
+impl<S: Stream + Unpin> Future for Next<'_, S> {
+    type Output = Option<S::Item>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut *self.0).poll_next(cx)
     }
 }
 
@@ -135,8 +477,21 @@ pub fn fmt_join<T: Display>(t: &[T], sep: &str, f: &mut Formatter<'_>) -> fmt::R
     Ok(())
 }
 
-fn remove_first_line(buf: &mut Bytes) -> Option<Bytes> {
-    if let Some(i) = memchr::memmem::find(buf, b"\r\n") {
+/// Returns `true` if a line of `len` bytes is longer than `max_length`.
+fn exceeds(max_length: Option<usize>, len: usize) -> bool {
+    max_length.map_or(false, |max| len > max)
+}
+
+fn remove_first_line(buf: &mut Bytes, lf: bool) -> Option<Bytes> {
+    if lf {
+        let i = memchr::memchr(b'\n', buf)?;
+        let mut line = buf.split_to(i + 1);
+        line.truncate(i); // Drop the LF
+        if line.last() == Some(&b'\r') {
+            line.truncate(i - 1);
+        }
+        Some(line)
+    } else if let Some(i) = memchr::memmem::find(buf, b"\r\n") {
         let mut line = buf.split_to(i + 2);
         line.truncate(i); // Drop the CRLF
         Some(line)
@@ -145,14 +500,11 @@ fn remove_first_line(buf: &mut Bytes) -> Option<Bytes> {
     }
 }
 
-fn concat_bytes(a: &[u8], b: Bytes) -> Bytes {
-    if a.is_empty() {
-        b
-    } else {
-        let mut buf = Vec::with_capacity(a.len() + b.len());
-        buf.extend_from_slice(a);
-        buf.extend_from_slice(&b);
-        buf.into()
+/// Reserves space for `additional` bytes in `buf`, allocating at least `capacity` bytes
+/// if a reallocation is needed.
+fn reserve(buf: &mut BytesMut, additional: usize, capacity: usize) {
+    if buf.capacity() - buf.len() < additional {
+        buf.reserve(additional.max(capacity));
     }
 }
 
@@ -209,13 +561,202 @@ mod test {
         ];
 
         let concat = body.concat();
-        let expected = concat.split("\r\n");
+        let expected = concat.split("\r\n").collect::<Vec<_>>();
+        for &capacity in &[0, 2, 1024] {
+            let lines = Lines::with_capacity(
+                StreamBody {
+                    stream: stream::iter(&body).map(|&c| Ok(Bytes::from_static(c.as_bytes()))),
+                },
+                capacity,
+            );
+            let lines = block_on_stream(lines)
+                .map(|s: Result<_, Error>| String::from_utf8(s.unwrap().to_vec()).unwrap());
+
+            assert_eq!(lines.collect::<Vec<_>>(), expected);
+        }
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn lines_lf() {
+        let body = ["abc\n", "d\r\nefg\r", "\n", "\nhi\r\rjk", "\n", "lmn"];
+
         let lines = Lines::new(StreamBody {
             stream: stream::iter(&body).map(|&c| Ok(Bytes::from_static(c.as_bytes()))),
-        });
+        })
+        .lf(true);
+        let lines = block_on_stream(lines)
+            .map(|s: Result<_, Error>| String::from_utf8(s.unwrap().to_vec()).unwrap());
+
+        assert_eq!(
+            lines.collect::<Vec<_>>(),
+            ["abc", "d", "efg", "", "hi\r\rjk", "lmn"],
+        );
+    }
+
+    #[test]
+    fn utf8_split() {
+        // A multi-byte character (`E3 81 82`) split across the chunks.
+        let bodies: [(bool, &[&[u8]]); 2] = [
+            (false, &[b"\"\xE3", b"\x81", b"\x82\"\r\n"]),
+            (true, &[b"7\r\n\"\xE3", b"\x81", b"\x82\"\r\n"]),
+        ];
+
+        for &(delimited, body) in &bodies {
+            let lines = Lines::new(StreamBody {
+                stream: stream::iter(body).map(|&c| Ok(Bytes::from_static(c))),
+            })
+            .delimited(delimited);
+            let lines = block_on_stream(lines)
+                .map(|s: Result<_, Error>| String::from_utf8(s.unwrap().to_vec()).unwrap());
+            assert_eq!(lines.collect::<Vec<_>>(), ["\"\u{3042}\""]);
+        }
+    }
+
+    #[test]
+    fn max_length() {
+        let to_string = |s: Result<Bytes, Error>| s.map(|s| String::from_utf8(s.to_vec()).unwrap());
+
+        let body = ["abcde\r\n", "fgh", "ijk", "\r\n"];
+        let lines = Lines::new(StreamBody {
+            stream: stream::iter(&body).map(|&c| Ok(Bytes::from_static(c.as_bytes()))),
+        })
+        .max_length(Some(5));
+        let mut lines = block_on_stream(lines).map(to_string);
+        assert_eq!(lines.next().unwrap().unwrap(), "abcde");
+        assert!(matches!(lines.next(), Some(Err(Error::LineTooLong))));
+        assert!(lines.next().is_none());
+
+        // The result must not depend on how the body is split into chunks.
+        let bodies: [&[&str]; 4] = [
+            &["abcde\r\nabcdef\r\n"],
+            &["abcde\r", "\nabc", "def\r\n"],
+            &["abc", "de\r\nabcd", "ef\r\n"],
+            &["abcde\r\nabcde", "f"],
+        ];
+        for body in &bodies {
+            let lines = Lines::new(StreamBody {
+                stream: stream::iter(*body).map(|&c| Ok(Bytes::from_static(c.as_bytes()))),
+            })
+            .max_length(Some(5));
+            let mut lines = block_on_stream(lines).map(to_string);
+            assert_eq!(lines.next().unwrap().unwrap(), "abcde", "{:?}", body);
+            assert!(
+                matches!(lines.next(), Some(Err(Error::LineTooLong))),
+                "{:?}",
+                body,
+            );
+            assert!(lines.next().is_none(), "{:?}", body);
+        }
+
+        let body = ["4\r\n{}\r\n", "100\r\n{\"id\":"];
+        let lines = Lines::new(StreamBody {
+            stream: stream::iter(&body).map(|&c| Ok(Bytes::from_static(c.as_bytes()))),
+        })
+        .delimited(true)
+        .max_length(Some(10));
+        let mut lines = block_on_stream(lines).map(to_string);
+        assert_eq!(lines.next().unwrap().unwrap(), "{}");
+        assert!(matches!(lines.next(), Some(Err(Error::LineTooLong))));
+        assert!(lines.next().is_none());
+    }
+
+    #[test]
+    fn trailers() {
+        let (mut tx, body) = hyper_pkg::Body::channel();
+        let mut trailers = HeaderMap::new();
+        trailers.insert("grpc-status", http::HeaderValue::from_static("0"));
+        tx.try_send_data(Bytes::from_static(b"abc\r\n")).unwrap();
+        let send_trailers = tx.send_trailers(trailers.clone());
+        futures::executor::block_on(send_trailers).unwrap();
+        drop(tx);
+
+        let mut lines = Box::pin(Lines::new(body));
+        let line = futures::executor::block_on(lines.next()).unwrap();
+        assert_eq!(line.unwrap(), "abc");
+        assert_eq!(lines.trailers(), None);
+        assert!(futures::executor::block_on(lines.next()).is_none());
+        assert_eq!(lines.trailers(), Some(&trailers));
+    }
+
+    #[test]
+    fn delimited() {
+        let body = [
+            "\r\n",
+            "15\r\n{\"text\":\"\r\n\"}\r\n",
+            "10\r",
+            "\n{\"id\"",
+            ":1}\r\n\r\n1",
+            "0\r\n{\"id\":2}\r\n",
+        ];
+
+        let lines = Lines::new(StreamBody {
+            stream: stream::iter(&body).map(|&c| Ok(Bytes::from_static(c.as_bytes()))),
+        })
+        .delimited(true);
         let lines = block_on_stream(lines)
             .map(|s: Result<_, Error>| String::from_utf8(s.unwrap().to_vec()).unwrap());
 
-        assert_eq!(lines.collect::<Vec<_>>(), expected.collect::<Vec<_>>());
+        assert_eq!(
+            lines.collect::<Vec<_>>(),
+            ["", "{\"text\":\"\r\n\"}", "{\"id\":1}", "", "{\"id\":2}"],
+        );
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn idle_timeout() {
+        let chunk = Bytes::from_static(b"abc\r\n");
+        let lines = Lines::new(StreamBody {
+            stream: stream::iter(Some(Ok::<_, Error>(chunk))).chain(stream::pending()),
+        })
+        .idle_timeout(Some(Duration::from_millis(10)));
+        futures::pin_mut!(lines);
+
+        assert_eq!(lines.next().await.unwrap().unwrap(), "abc");
+        assert!(matches!(lines.next().await, Some(Err(Error::TimedOut))));
+        assert!(lines.next().await.is_none());
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn tee() {
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone, Default)]
+        struct Writer(Arc<Mutex<Vec<u8>>>);
+
+        impl tokio::io::AsyncWrite for Writer {
+            fn poll_write(
+                self: Pin<&mut Self>,
+                _: &mut Context<'_>,
+                buf: &[u8],
+            ) -> Poll<io::Result<usize>> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Poll::Ready(Ok(buf.len()))
+            }
+
+            fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+
+            fn poll_shutdown(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<io::Result<()>> {
+                Poll::Ready(Ok(()))
+            }
+        }
+
+        let body = ["abc\r\n", "\r\nd", "ef\r\n"];
+        let mut lines = Lines::new(StreamBody {
+            stream: stream::iter(&body).map(|&c| Ok(Bytes::from_static(c.as_bytes()))),
+        });
+        let writer = Writer::default();
+        lines.tee(writer.clone());
+        let lines = lines
+            .map(|s: Result<_, Error>| s.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+
+        assert_eq!(lines, ["abc", "", "def"]);
+        assert_eq!(*writer.0.lock().unwrap(), body.concat().as_bytes());
     }
 }